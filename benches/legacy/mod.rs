@@ -0,0 +1,195 @@
+// Previous parser of drawio files, based on serde, which parses all the pages at once.
+// It's only kept to compare the performances of the parsers, so it deserializes its own model,
+// converted to the model of the library.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use drawio_exporter::core::drawio::mxfile::{
+    decompress_diagram, Diagram, Element, MxCell, MxGraphModel, Mxfile, Root, UserObject,
+};
+use serde::{Deserialize, Deserializer};
+
+#[derive(Debug, Deserialize, Default)]
+struct LegacyMxCell {
+    id: Option<String>,
+    value: Option<String>,
+    link: Option<String>,
+    parent: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyUserObject {
+    id: Option<String>,
+    label: Option<String>,
+    link: Option<String>,
+    tooltip: Option<String>,
+    #[serde(rename = "mxCell")]
+    cell: Option<LegacyMxCell>,
+    #[serde(flatten, deserialize_with = "deserialize_attributes")]
+    attributes: BTreeMap<String, String>,
+}
+
+/// Keep the other attributes of an element, but not its children
+fn deserialize_attributes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AttributeOrChild {
+        Attribute(String),
+        Child(serde::de::IgnoredAny),
+    }
+
+    let attributes_or_children: BTreeMap<String, AttributeOrChild> =
+        BTreeMap::deserialize(deserializer)?;
+    Ok(attributes_or_children
+        .into_iter()
+        .filter_map(|(name, value)| match value {
+            AttributeOrChild::Attribute(value) => Some((name, value)),
+            AttributeOrChild::Child(_) => None,
+        })
+        .collect())
+}
+
+#[derive(Debug, Deserialize)]
+enum LegacyElement {
+    #[serde(rename = "mxCell")]
+    MxCell(LegacyMxCell),
+    // draw.io uses both names for shapes with custom properties
+    #[serde(alias = "object")]
+    UserObject(LegacyUserObject),
+    #[serde(other, deserialize_with = "deserialize_ignore_any")]
+    Other,
+}
+
+fn deserialize_ignore_any<'de, D: Deserializer<'de>>(deserializer: D) -> Result<(), D::Error> {
+    serde::de::IgnoredAny::deserialize(deserializer)?;
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct LegacyRoot {
+    #[serde(rename = "$value")]
+    elements: Vec<LegacyElement>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct LegacyMxGraphModel {
+    #[serde(rename = "root", default)]
+    root: LegacyRoot,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyDiagram {
+    id: String,
+    name: String,
+    #[serde(rename = "mxGraphModel", default)]
+    mx_graph_model: LegacyMxGraphModel,
+}
+
+#[derive(Debug, Deserialize)]
+struct LegacyMxfile {
+    #[serde(rename = "diagram", default)]
+    diagrams: Vec<LegacyDiagram>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompressDiagram {
+    id: String,
+    name: String,
+    #[serde(rename = "$value")]
+    raw_diagram: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MxfileWithCompressDiagrams {
+    #[serde(rename = "diagram", default)]
+    diagrams: Vec<CompressDiagram>,
+}
+
+impl From<LegacyMxCell> for MxCell {
+    fn from(cell: LegacyMxCell) -> MxCell {
+        MxCell {
+            id: cell.id,
+            value: cell.value,
+            link: cell.link,
+            parent: cell.parent,
+        }
+    }
+}
+
+impl From<LegacyMxGraphModel> for MxGraphModel {
+    fn from(mx_graph_model: LegacyMxGraphModel) -> MxGraphModel {
+        let elements = mx_graph_model
+            .root
+            .elements
+            .into_iter()
+            .map(|element| match element {
+                LegacyElement::MxCell(cell) => Element::MxCell(cell.into()),
+                LegacyElement::UserObject(user_object) => Element::UserObject(UserObject {
+                    id: user_object.id,
+                    label: user_object.label,
+                    link: user_object.link,
+                    tooltip: user_object.tooltip,
+                    cell: user_object.cell.map(MxCell::from),
+                    attributes: user_object.attributes,
+                }),
+                LegacyElement::Other => Element::Other,
+            })
+            .collect();
+        MxGraphModel {
+            root: Root { elements },
+        }
+    }
+}
+
+pub fn read_content(path: &Path, content: String) -> Result<Mxfile> {
+    match content.is_empty() {
+        true => Ok(Mxfile::default()),
+        false => parse_compressed_content(path, content.clone())
+            .or_else(|_| parse_uncompressed_content(path, content)),
+    }
+}
+
+fn parse_compressed_content(path: &Path, content: String) -> Result<Mxfile> {
+    let mxfile_with_compressed_diagrams: MxfileWithCompressDiagrams =
+        serde_xml_rs::from_reader(content.as_bytes())
+            .with_context(|| format!("can parse xml on {}", path.display()))?;
+    let mxfile = decompress(mxfile_with_compressed_diagrams)
+        .with_context(|| format!("can uncompress xml on {}", path.display()))?;
+    Ok(mxfile)
+}
+
+fn decompress(mxfile_with_compressed_diagrams: MxfileWithCompressDiagrams) -> Result<Mxfile> {
+    let mut diagrams: Vec<Diagram> = vec![];
+    for compressed_diagram in mxfile_with_compressed_diagrams.diagrams {
+        let xml_diagram = decompress_diagram(&compressed_diagram.raw_diagram)?;
+        let mx_graph_model: LegacyMxGraphModel = serde_xml_rs::from_reader(xml_diagram.as_bytes())?;
+        diagrams.push(Diagram::from_model(
+            compressed_diagram.id,
+            compressed_diagram.name,
+            mx_graph_model.into(),
+        ))
+    }
+
+    // A file without any diagram can't be considered as compressed
+    let mut mxfile = Mxfile::default();
+    mxfile.compressed = !diagrams.is_empty();
+    mxfile.diagrams = diagrams;
+    Ok(mxfile)
+}
+
+fn parse_uncompressed_content(path: &Path, content: String) -> Result<Mxfile> {
+    let mxfile: LegacyMxfile = serde_xml_rs::from_reader(content.as_bytes())
+        .with_context(|| format!("can parse xml on {}", path.display()))?;
+    let diagrams = mxfile
+        .diagrams
+        .into_iter()
+        .map(|diagram| Diagram::from_model(diagram.id, diagram.name, diagram.mx_graph_model.into()))
+        .collect();
+    let mut mxfile = Mxfile::default();
+    mxfile.diagrams = diagrams;
+    Ok(mxfile)
+}
@@ -0,0 +1,88 @@
+mod legacy;
+
+use std::path::Path;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use drawio_exporter::core::drawio::mxfile::{read_content, Mxfile};
+
+/// Uncompressed drawio file with some pages full of shapes, with and without custom properties
+fn large_drawio_file(pages: usize, cells: usize) -> String {
+    let mut content = String::from("<mxfile host=\"Electron\">");
+    for page in 0..pages {
+        content.push_str(&format!(
+            "<diagram id=\"page-{page}\" name=\"Page-{page}\"><mxGraphModel><root>\
+             <mxCell id=\"0\"/><mxCell id=\"1\" parent=\"0\"/>"
+        ));
+        for cell in 0..cells {
+            match cell % 2 {
+                0 => content.push_str(&format!(
+                    "<mxCell id=\"cell-{cell}\" value=\"Shape &lt;b&gt;{cell}&lt;/b&gt;\" \
+                     style=\"rounded=1;whiteSpace=wrap;html=1;\" vertex=\"1\" parent=\"1\">\
+                     <mxGeometry x=\"{cell}\" y=\"0\" width=\"120\" height=\"60\" as=\"geometry\"/>\
+                     </mxCell>"
+                )),
+                _ => content.push_str(&format!(
+                    "<UserObject label=\"Object {cell}\" link=\"https://example.com/{cell}\" \
+                     owner=\"team\" id=\"object-{cell}\">\
+                     <mxCell style=\"rounded=0;\" vertex=\"1\" parent=\"1\">\
+                     <mxGeometry x=\"{cell}\" y=\"100\" width=\"120\" height=\"60\" as=\"geometry\"/>\
+                     </mxCell></UserObject>"
+                )),
+            }
+        }
+        content.push_str("</root></mxGraphModel></diagram>");
+    }
+    content.push_str("</mxfile>");
+    content
+}
+
+fn parse_all_pages(mxfile: &Mxfile) -> usize {
+    mxfile
+        .diagrams
+        .iter()
+        .map(|diagram| diagram.mx_graph_model().unwrap().root.elements.len())
+        .sum()
+}
+
+fn parser_benchmark(c: &mut Criterion) {
+    let path = Path::new("large.drawio");
+    let mut group = c.benchmark_group("parser");
+    for cells in [100, 1000, 10000] {
+        let content = large_drawio_file(10, cells);
+        group.bench_with_input(BenchmarkId::new("serde", cells), &content, |b, content| {
+            b.iter(|| {
+                let mxfile = legacy::read_content(path, black_box(content.clone())).unwrap();
+                parse_all_pages(&mxfile)
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("streaming", cells),
+            &content,
+            |b, content| {
+                b.iter(|| {
+                    let mxfile = read_content(path, black_box(content.clone())).unwrap();
+                    parse_all_pages(&mxfile)
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("streaming-first-page", cells),
+            &content,
+            |b, content| {
+                b.iter(|| {
+                    let mxfile = read_content(path, black_box(content.clone())).unwrap();
+                    mxfile.diagrams[0]
+                        .mx_graph_model()
+                        .unwrap()
+                        .root
+                        .elements
+                        .len()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parser_benchmark);
+criterion_main!(benches);
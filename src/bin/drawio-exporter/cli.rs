@@ -2,19 +2,25 @@ use anyhow::Result;
 use clap::Command;
 
 use crate::commands;
-use crate::commands::global_exec;
+use crate::commands::{builtin_exec, global_exec};
 
 pub fn main() -> Result<()> {
     let matches = cli().get_matches();
-    let command_exec = global_exec();
-    command_exec(&matches)
+    let (command_exec, command_matches) = match matches.subcommand() {
+        Some((cmd, args)) => (builtin_exec(cmd).unwrap(), args),
+        // Without subcommand, we keep exporting for backward compatibility
+        None => (global_exec(), &matches),
+    };
+    command_exec(command_matches)
 }
 
 fn cli() -> Command {
     let mut command = Command::new("drawio-exporter")
         .about("Command Line Client To Enhance Files Export Using Draw.io Application")
         .version(crate_version!())
-        .long_version(crate_version!());
+        .long_version(crate_version!())
+        .args_conflicts_with_subcommands(true)
+        .subcommands(commands::builtin());
 
     for arg in commands::global_args() {
         command = command.arg(arg);
@@ -0,0 +1,57 @@
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use drawio_exporter::ops::book::{book, BookOptions};
+
+use crate::commands::{explore_args, explore_options};
+
+pub fn cli() -> Command {
+    Command::new("book")
+        .about("Combine the exported pdf files of all pages into a pdf book, with a cover and a table of contents")
+        .arg(
+            Arg::new("file")
+                .help("Book pdf file")
+                .value_name("file")
+                .default_value("book.pdf")
+                .long("file"),
+        )
+        .arg(
+            Arg::new("title")
+                .help("Title on the cover of the book")
+                .value_name("title")
+                .default_value("Diagrams")
+                .long("title"),
+        )
+        .arg(
+            Arg::new("folder")
+                .help("Exported folder name")
+                .value_name("folder")
+                .default_value("export")
+                .short('o')
+                .long("output"),
+        )
+        .arg(
+            Arg::new("remove-page-suffix")
+                .help("Remove page suffix when possible (in case of single page file)")
+                .long("remove-page-suffix")
+                .action(ArgAction::SetTrue),
+        )
+        .args(explore_args())
+        .arg(
+            Arg::new("path")
+                .help("Path to the drawio files to combine")
+                .value_name("PATH")
+                .default_value(".")
+                .index(1),
+        )
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    book(BookOptions {
+        folder: args.get_one("folder").unwrap(),
+        remove_page_suffix: args.get_one::<bool>("remove-page-suffix").copied().unwrap(),
+        file: args.get_one("file").unwrap(),
+        title: args.get_one("title").unwrap(),
+        explore_options: &explore_options(args),
+        path: args.get_one::<String>("path").unwrap(),
+    })
+}
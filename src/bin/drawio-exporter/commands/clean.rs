@@ -0,0 +1,34 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+use drawio_exporter::ops::clean::{clean, CleanOptions};
+
+use crate::commands::{explore_args, explore_options};
+
+pub fn cli() -> Command {
+    Command::new("clean")
+        .about("Remove export folders next to drawio files")
+        .arg(
+            Arg::new("folder")
+                .help("Exported folder name")
+                .value_name("folder")
+                .default_value("export")
+                .short('o')
+                .long("output"),
+        )
+        .args(explore_args())
+        .arg(
+            Arg::new("path")
+                .help("Path to the drawio files to clean")
+                .value_name("PATH")
+                .default_value(".")
+                .index(1),
+        )
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    clean(CleanOptions {
+        folder: args.get_one("folder").unwrap(),
+        explore_options: &explore_options(args),
+        path: args.get_one::<String>("path").unwrap(),
+    })
+}
@@ -0,0 +1,33 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+use drawio_exporter::ops::diff::{diff, DiffOptions};
+
+use crate::commands::{explore_args, explore_options};
+
+pub fn cli() -> Command {
+    Command::new("diff")
+        .about("Show page changes of drawio files since a git reference")
+        .arg(
+            Arg::new("git-reference")
+                .help("Any git reference (branch, tag, commit id, ...)")
+                .value_name("reference")
+                .default_value("HEAD")
+                .long("git-ref"),
+        )
+        .args(explore_args())
+        .arg(
+            Arg::new("path")
+                .help("Path to the drawio files to compare")
+                .value_name("PATH")
+                .default_value(".")
+                .index(1),
+        )
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    diff(DiffOptions {
+        git_reference: args.get_one("git-reference").unwrap(),
+        explore_options: &explore_options(args),
+        path: args.get_one::<String>("path").unwrap(),
+    })
+}
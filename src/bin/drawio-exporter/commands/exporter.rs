@@ -1,20 +1,99 @@
+use std::time::Duration;
+
 use anyhow::Result;
-use clap::{Arg, ArgAction, ArgMatches};
-use drawio_exporter::core::drawio::drawio_desktop::os_default_application;
-use drawio_exporter::ops::exporter::{exporter, ExporterOptions};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use drawio_exporter::core::drawio::drawio_desktop::{
+    check_drawio_argument, DrawioDesktop, DrawioDesktopOptions,
+};
+use drawio_exporter::core::drawio::export_backend::{ExportBackend, ExportServer};
+use drawio_exporter::core::drawio::virtual_display::DisplayMode;
+use drawio_exporter::core::interrupt::handle_interrupts;
+use drawio_exporter::core::post_processing::{PostProcessStep, PostProcessing};
+use drawio_exporter::ops::exporter::{exporter, ExporterOptions, PageRange};
+
+use crate::commands::{explore_args, explore_options, parse_variable, variable_arg, variables};
+
+pub fn cli() -> Command {
+    Command::new("export")
+        .about("Export drawio files (default command)")
+        .args(args())
+}
 
 pub fn args() -> Vec<Arg> {
-    vec![
+    let mut args = vec![
         // Cli options
         Arg::new("application")
-            .help("Draw.io Desktop Application")
+            .help("Draw.io Desktop Application (discovered when not set)")
             .value_name("path")
-            .default_value(os_default_application())
             .short('A')
             .long("application"),
         Arg::new("drawio-desktop-headless")
             .help("Enable Draw.io Desktop headless mode")
             .long("drawio-desktop-headless"),
+        Arg::new("virtual-display")
+            .help("Display of Draw.io Desktop, auto uses a virtual display on Linux when no display is set (Xvfb, or offscreen rendering when Xvfb isn't installed)")
+            .value_name("mode")
+            .value_parser(["auto", "xvfb", "offscreen", "off"])
+            .default_value("auto")
+            .long("virtual-display"),
+        Arg::new("xvfb")
+            .help("Xvfb program used for the virtual display")
+            .value_name("path")
+            .default_value("Xvfb")
+            .long("xvfb"),
+        Arg::new("drawio-arg")
+            .help("Additional argument of Draw.io Desktop, like --svg-theme=dark (can be repeated)")
+            .value_name("--name[=value]")
+            .value_parser(parse_drawio_argument)
+            .allow_hyphen_values(true)
+            .long("drawio-arg")
+            .action(ArgAction::Append),
+        Arg::new("drawio-env")
+            .help("Environment variable set for Draw.io Desktop (can be repeated)")
+            .value_name("name=value")
+            .value_parser(parse_variable)
+            .long("drawio-env")
+            .action(ArgAction::Append),
+        Arg::new("drawio-env-remove")
+            .help("Environment variable removed for Draw.io Desktop (can be repeated)")
+            .value_name("name")
+            .long("drawio-env-remove")
+            .action(ArgAction::Append),
+        Arg::new("drawio-cwd")
+            .help("Working directory of Draw.io Desktop")
+            .value_name("path")
+            .long("drawio-cwd"),
+        Arg::new("export-server")
+            .help("Export using a draw.io export server, instead of Draw.io Desktop (jpg, pdf, png, and svg formats)")
+            .value_name("url")
+            .long("export-server")
+            .conflicts_with_all([
+                "application",
+                "drawio-desktop-headless",
+                "virtual-display",
+                "xvfb",
+                "drawio-arg",
+                "drawio-env",
+                "drawio-env-remove",
+                "drawio-cwd",
+            ]),
+        Arg::new("timeout")
+            .help("Timeout in seconds of each export, Draw.io Desktop is killed when it's reached (no timeout by default)")
+            .value_name("seconds")
+            .value_parser(clap::value_parser!(u64))
+            .long("timeout"),
+        Arg::new("retries")
+            .help("Retry an export after a transient failure (like a timeout, or a crash)")
+            .value_name("count")
+            .value_parser(clap::value_parser!(u32))
+            .default_value("0")
+            .long("retries"),
+        Arg::new("retry-delay")
+            .help("Delay in seconds before the first retry, doubled on each retry")
+            .value_name("seconds")
+            .value_parser(clap::value_parser!(u64))
+            .default_value("1")
+            .long("retry-delay"),
         Arg::new("format")
             .help("Exported format")
             .value_name("format")
@@ -43,6 +122,35 @@ pub fn args() -> Vec<Arg> {
             .help("Remove page suffix when possible (in case of single page file)")
             .long("remove-page-suffix")
             .action(ArgAction::SetTrue),
+        Arg::new("batch")
+            .help("Export all the pages of a file with a single draw.io execution (for adoc, md, jpg, png, and svg formats)")
+            .long("batch")
+            .action(ArgAction::SetTrue),
+        Arg::new("single-pdf")
+            .help("Export all the pages of a drawio file into a single pdf file, named after the drawio file (for pdf format)")
+            .long("single-pdf")
+            .action(ArgAction::SetTrue),
+        Arg::new("page-range")
+            .help("Pages exported into the single pdf file, numbered from 1 (like 2..4)")
+            .value_name("from..to")
+            .value_parser(parse_page_range)
+            .long("page-range")
+            .requires("single-pdf"),
+        Arg::new("post-process")
+            .help("Post-processing steps of the exported files of a format, like png:optimize,scale=2x,webp (steps: optimize and webp for png, minify for svg, strip-metadata for png and svg, scale=<factor>x for png and jpg)")
+            .value_name("format:step[,step...]")
+            .value_parser(parse_post_processing)
+            .long("post-process")
+            .action(ArgAction::Append),
+        Arg::new("search-index")
+            .help("Write the text content of the exported pages as a JSON search index")
+            .value_name("file")
+            .long("search-index"),
+        Arg::new("text-alternative")
+            .help("Use the text content of the page as alternative text for adoc and md formats")
+            .long("text-alternative")
+            .action(ArgAction::SetTrue),
+        variable_arg(),
         Arg::new("path")
             .help("Path to the drawio files to export")
             .value_name("PATH")
@@ -107,17 +215,83 @@ pub fn args() -> Vec<Arg> {
             .short('e')
             .long("embed-diagram")
             .action(ArgAction::SetTrue),
-    ]
+    ];
+    args.extend(explore_args());
+    args
+}
+
+fn parse_drawio_argument(argument: &str) -> Result<String, String> {
+    check_drawio_argument(argument)
+        .map(|_| argument.to_string())
+        .map_err(|err| err.to_string())
+}
+
+fn parse_page_range(page_range: &str) -> Result<PageRange, String> {
+    PageRange::parse(page_range).map_err(|err| err.to_string())
+}
+
+fn parse_post_processing(configuration: &str) -> Result<(String, Vec<PostProcessStep>), String> {
+    PostProcessing::parse(configuration).map_err(|err| err.to_string())
 }
 
 pub fn exec(args: &ArgMatches) -> Result<()> {
+    let variables = variables(args);
+    let mut post_processing = PostProcessing::new();
+    for (format, steps) in args
+        .get_many::<(String, Vec<PostProcessStep>)>("post-process")
+        .unwrap_or_default()
+    {
+        post_processing.add(format.clone(), steps.clone());
+    }
+    let drawio_arguments: Vec<String> = args
+        .get_many::<String>("drawio-arg")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let drawio_environment: Vec<(String, String)> = args
+        .get_many::<(String, String)>("drawio-env")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let drawio_removed_environment: Vec<String> = args
+        .get_many::<String>("drawio-env-remove")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    let timeout = args
+        .get_one::<u64>("timeout")
+        .copied()
+        .map(Duration::from_secs);
+    let backend: Box<dyn ExportBackend> = match args.get_one::<String>("export-server") {
+        Some(url) => Box::new(ExportServer::new(url, timeout)),
+        None => Box::new(DrawioDesktop::new(DrawioDesktopOptions {
+            application: args.get_one("application"),
+            is_headless: args.contains_id("drawio-desktop-headless"),
+            timeout,
+            display_mode: match args.get_one::<String>("virtual-display").unwrap().as_str() {
+                "xvfb" => DisplayMode::Xvfb,
+                "offscreen" => DisplayMode::Offscreen,
+                "off" => DisplayMode::Off,
+                _ => DisplayMode::Auto,
+            },
+            xvfb: args.get_one::<String>("xvfb").unwrap(),
+            arguments: &drawio_arguments,
+            environment: &drawio_environment,
+            removed_environment: &drawio_removed_environment,
+            current_dir: args.get_one("drawio-cwd"),
+        })),
+    };
+    // The started processes (draw.io, Xvfb) are stopped before exiting on Ctrl-C
+    handle_interrupts()?;
     exporter(ExporterOptions {
-        application: args.get_one("application").unwrap(),
-        drawio_desktop_headless: args.contains_id("drawio-desktop-headless"),
+        backend: backend.as_ref(),
+        retries: *args.get_one::<u32>("retries").unwrap(),
+        retry_delay: Duration::from_secs(*args.get_one::<u64>("retry-delay").unwrap()),
         folder: args.get_one("folder").unwrap(),
         on_filesystem_changes: args.get_one::<bool>("on-changes").copied().unwrap(),
         on_git_changes_since_reference: args.get_one("git-reference"),
         remove_page_suffix: args.get_one::<bool>("remove-page-suffix").copied().unwrap(),
+        explore_options: &explore_options(args),
         path: args.get_one::<String>("path").unwrap(),
         format: args.get_one("format").unwrap(),
         border: args.get_one("drawio-cli-border").unwrap(),
@@ -149,5 +323,12 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
             .get_one::<bool>("drawio-cli-pdf-png-svg-embed-diagram")
             .copied()
             .unwrap(),
+        batch: args.get_one::<bool>("batch").copied().unwrap(),
+        single_pdf: args.get_one::<bool>("single-pdf").copied().unwrap(),
+        page_range: args.get_one::<PageRange>("page-range").copied(),
+        post_processing: &post_processing,
+        search_index: args.get_one("search-index"),
+        text_alternative: args.get_one::<bool>("text-alternative").copied().unwrap(),
+        variables: &variables,
     })
 }
@@ -0,0 +1,32 @@
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use drawio_exporter::ops::formatter::{formatter, FormatterOptions};
+
+use crate::commands::{explore_args, explore_options};
+
+pub fn cli() -> Command {
+    Command::new("fmt")
+        .about("Format drawio files for stable diffs (uncompressed, sorted, and without volatile attributes)")
+        .arg(
+            Arg::new("check")
+                .help("Only check that the files are formatted, and fail if not")
+                .long("check")
+                .action(ArgAction::SetTrue),
+        )
+        .args(explore_args())
+        .arg(
+            Arg::new("path")
+                .help("Path to the drawio files to format")
+                .value_name("PATH")
+                .default_value(".")
+                .index(1),
+        )
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    formatter(FormatterOptions {
+        check: args.get_flag("check"),
+        explore_options: &explore_options(args),
+        path: args.get_one::<String>("path").unwrap(),
+    })
+}
@@ -0,0 +1,72 @@
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use drawio_exporter::core::http::{HttpChecker, HttpClientChecker};
+use drawio_exporter::ops::links::{links, links_check, LinksCheckOptions, LinksOptions};
+use std::time::Duration;
+
+use crate::commands::{explore_args, explore_options, variable_arg, variables};
+
+pub fn cli() -> Command {
+    Command::new("links")
+        .about("Show links extracted from drawio files")
+        .args_conflicts_with_subcommands(true)
+        .args(explore_args())
+        .arg(variable_arg())
+        .arg(path_arg("Path to the drawio files to explore"))
+        .subcommand(
+            Command::new("check")
+                .about("Check that links of drawio files are not dead")
+                .arg(
+                    Arg::new("no-http")
+                        .help("Don't check http(s) links")
+                        .long("no-http")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("http-timeout")
+                        .help("Timeout in seconds of http(s) links check")
+                        .value_name("seconds")
+                        .value_parser(clap::value_parser!(u64))
+                        .default_value("10")
+                        .long("http-timeout"),
+                )
+                .args(explore_args())
+                .arg(variable_arg())
+                .arg(path_arg("Path to the drawio files to check")),
+        )
+}
+
+fn path_arg(help: &'static str) -> Arg {
+    Arg::new("path")
+        .help(help)
+        .value_name("PATH")
+        .default_value(".")
+        .index(1)
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    match args.subcommand() {
+        Some(("check", check_args)) => exec_check(check_args),
+        _ => links(LinksOptions {
+            variables: &variables(args),
+            explore_options: &explore_options(args),
+            path: args.get_one::<String>("path").unwrap(),
+        }),
+    }
+}
+
+fn exec_check(args: &ArgMatches) -> Result<()> {
+    let http_checker = HttpClientChecker::new(Duration::from_secs(
+        *args.get_one::<u64>("http-timeout").unwrap(),
+    ));
+    let no_http = args.get_one::<bool>("no-http").copied().unwrap();
+    links_check(LinksCheckOptions {
+        http_checker: match no_http {
+            true => None,
+            false => Some(&http_checker as &dyn HttpChecker),
+        },
+        variables: &variables(args),
+        explore_options: &explore_options(args),
+        path: args.get_one::<String>("path").unwrap(),
+    })
+}
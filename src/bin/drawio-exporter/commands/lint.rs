@@ -0,0 +1,76 @@
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use drawio_exporter::ops::lint::{lint, LintOptions, Rule, StoragePolicy};
+
+use crate::commands::{explore_args, explore_options};
+
+pub fn cli() -> Command {
+    let rule_names: Vec<&'static str> = Rule::all().iter().map(|rule| rule.name()).collect();
+    Command::new("lint")
+        .about("Check drawio files for common problems")
+        .arg(
+            Arg::new("rule")
+                .help("Only check the given rules (all rules by default)")
+                .value_name("rule")
+                .value_parser(rule_names.clone())
+                .long("rule")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("skip-rule")
+                .help("Don't check the given rules")
+                .value_name("rule")
+                .value_parser(rule_names)
+                .long("skip-rule")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("storage")
+                .help("Expected storage of the diagrams")
+                .value_name("storage")
+                .value_parser(["any", "compressed", "uncompressed"])
+                .default_value("any")
+                .long("storage"),
+        )
+        .arg(
+            Arg::new("json")
+                .help("Print the problems as JSON")
+                .long("json")
+                .action(ArgAction::SetTrue),
+        )
+        .args(explore_args())
+        .arg(
+            Arg::new("path")
+                .help("Path to the drawio files to check")
+                .value_name("PATH")
+                .default_value(".")
+                .index(1),
+        )
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    let skipped_rules: Vec<Rule> = args
+        .get_many::<String>("skip-rule")
+        .unwrap_or_default()
+        .filter_map(|name| Rule::from_name(name))
+        .collect();
+    let rules: Vec<Rule> = match args.get_many::<String>("rule") {
+        Some(names) => names.filter_map(|name| Rule::from_name(name)).collect(),
+        None => Rule::all(),
+    }
+    .into_iter()
+    .filter(|rule| !skipped_rules.contains(rule))
+    .collect();
+
+    lint(LintOptions {
+        rules,
+        storage: match args.get_one::<String>("storage").unwrap().as_str() {
+            "compressed" => StoragePolicy::Compressed,
+            "uncompressed" => StoragePolicy::Uncompressed,
+            _ => StoragePolicy::Any,
+        },
+        json: args.get_one::<bool>("json").copied().unwrap(),
+        explore_options: &explore_options(args),
+        path: args.get_one::<String>("path").unwrap(),
+    })
+}
@@ -0,0 +1,58 @@
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use drawio_exporter::ops::list::{list, ListOptions};
+
+use crate::commands::{explore_args, explore_options};
+
+pub fn cli() -> Command {
+    Command::new("list")
+        .about("List drawio files, their pages and export status")
+        .arg(
+            Arg::new("format")
+                .help("Exported format to check")
+                .value_name("format")
+                .value_parser(["adoc", "md", "jpg", "pdf", "png", "svg", "vsdx", "xml"])
+                .default_value("pdf")
+                .short('f')
+                .long("format"),
+        )
+        .arg(
+            Arg::new("folder")
+                .help("Exported folder name")
+                .value_name("folder")
+                .default_value("export")
+                .short('o')
+                .long("output"),
+        )
+        .arg(
+            Arg::new("remove-page-suffix")
+                .help("Remove page suffix when possible (in case of single page file)")
+                .long("remove-page-suffix")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("json")
+                .help("Print the list as JSON")
+                .long("json")
+                .action(ArgAction::SetTrue),
+        )
+        .args(explore_args())
+        .arg(
+            Arg::new("path")
+                .help("Path to the drawio files to list")
+                .value_name("PATH")
+                .default_value(".")
+                .index(1),
+        )
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    list(ListOptions {
+        folder: args.get_one("folder").unwrap(),
+        format: args.get_one("format").unwrap(),
+        remove_page_suffix: args.get_one::<bool>("remove-page-suffix").copied().unwrap(),
+        json: args.get_one::<bool>("json").copied().unwrap(),
+        explore_options: &explore_options(args),
+        path: args.get_one::<String>("path").unwrap(),
+    })
+}
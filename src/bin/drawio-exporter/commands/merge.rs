@@ -0,0 +1,31 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+use drawio_exporter::ops::split::{merge, MergeOptions};
+
+pub fn cli() -> Command {
+    Command::new("merge")
+        .about("Merge drawio files into a single multi-page drawio file")
+        .arg(
+            Arg::new("output")
+                .help("Merged drawio file")
+                .value_name("file")
+                .short('o')
+                .long("output")
+                .required(true),
+        )
+        .arg(
+            Arg::new("paths")
+                .help("Drawio files to merge, in the order of their pages")
+                .value_name("FILE")
+                .num_args(1..)
+                .required(true)
+                .index(1),
+        )
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    merge(MergeOptions {
+        output: args.get_one("output").unwrap(),
+        paths: args.get_many::<String>("paths").unwrap().collect(),
+    })
+}
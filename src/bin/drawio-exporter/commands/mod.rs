@@ -1,7 +1,22 @@
 use anyhow::Result;
-use clap::{Arg, ArgMatches};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use drawio_exporter::core::explorer::{ExploreOptions, DEFAULT_EXTENSIONS, IGNORE_FILENAME};
+use drawio_exporter::ops::PARTIAL_FAILURE_EXIT_CODE;
+use std::collections::BTreeMap;
+use std::env;
 
+mod book;
+mod clean;
+mod diff;
 mod exporter;
+mod fmt;
+mod links;
+mod lint;
+mod list;
+mod merge;
+mod normalize;
+mod split;
+mod text;
 
 pub fn global_args() -> Vec<Arg> {
     exporter::args()
@@ -10,3 +25,145 @@ pub fn global_args() -> Vec<Arg> {
 pub fn global_exec() -> fn(&ArgMatches) -> Result<()> {
     exporter::exec
 }
+
+pub fn builtin() -> Vec<Command> {
+    vec![
+        exporter::cli(),
+        list::cli(),
+        links::cli(),
+        lint::cli(),
+        diff::cli(),
+        clean::cli(),
+        text::cli(),
+        normalize::cli(),
+        fmt::cli(),
+        split::cli(),
+        merge::cli(),
+        book::cli(),
+    ]
+}
+
+pub fn builtin_exec(cmd: &str) -> Option<fn(&ArgMatches) -> Result<()>> {
+    let f = match cmd {
+        "export" => exporter::exec,
+        "list" => list::exec,
+        "links" => links::exec,
+        "lint" => lint::exec,
+        "diff" => diff::exec,
+        "clean" => clean::exec,
+        "text" => text::exec,
+        "normalize" => normalize::exec,
+        "fmt" => fmt::exec,
+        "split" => split::exec,
+        "merge" => merge::exec,
+        "book" => book::exec,
+        _ => return None,
+    };
+    Some(f)
+}
+
+// Environment variables with this prefix are used as placeholders variables
+const VARIABLE_ENV_PREFIX: &str = "DRAWIO_EXPORTER_VAR_";
+
+pub fn variable_arg() -> Arg {
+    Arg::new("variable")
+        .help(format!(
+            "Variable used to resolve placeholders (also read from {}<name> environment variables)",
+            VARIABLE_ENV_PREFIX
+        ))
+        .value_name("name=value")
+        .value_parser(parse_variable)
+        .long("var")
+        .action(ArgAction::Append)
+}
+
+pub fn parse_variable(variable: &str) -> Result<(String, String), String> {
+    match variable.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => Err(format!("'{}' must be formatted as name=value", variable)),
+    }
+}
+
+pub fn variables(args: &ArgMatches) -> BTreeMap<String, String> {
+    let mut variables: BTreeMap<String, String> = env::vars()
+        .filter_map(|(name, value)| {
+            name.strip_prefix(VARIABLE_ENV_PREFIX)
+                .filter(|name| !name.is_empty())
+                .map(|name| (name.to_string(), value))
+        })
+        .collect();
+    // Variables from the command line take precedence over the environment
+    if let Some(cli_variables) = args.get_many::<(String, String)>("variable") {
+        variables.extend(cli_variables.cloned());
+    }
+    variables
+}
+
+pub fn explore_args() -> Vec<Arg> {
+    vec![
+        Arg::new("extension")
+            .help(format!(
+                "Extension of the drawio files to explore (default: {})",
+                DEFAULT_EXTENSIONS.join(", ")
+            ))
+            .value_name("extension")
+            .long("extension")
+            .action(ArgAction::Append),
+        Arg::new("include")
+            .help("Only explore files matching the glob")
+            .value_name("glob")
+            .long("include")
+            .action(ArgAction::Append),
+        Arg::new("exclude")
+            .help(format!(
+                "Don't explore files matching the glob (also read from {} files)",
+                IGNORE_FILENAME
+            ))
+            .value_name("glob")
+            .long("exclude")
+            .action(ArgAction::Append),
+        Arg::new("hidden")
+            .help("Explore hidden files and folders")
+            .long("hidden")
+            .action(ArgAction::SetTrue),
+        Arg::new("follow-symlinks")
+            .help("Follow symbolic links")
+            .long("follow-symlinks")
+            .action(ArgAction::SetTrue),
+        Arg::new("max-depth")
+            .help("Maximum depth of the exploration")
+            .value_name("depth")
+            .value_parser(clap::value_parser!(usize))
+            .long("max-depth"),
+        Arg::new("no-gitignore")
+            .help("Explore files ignored by git")
+            .long("no-gitignore")
+            .action(ArgAction::SetTrue),
+        Arg::new("keep-going")
+            .help(format!(
+                "Keep going when a file fails, and exit with code {} at the end",
+                PARTIAL_FAILURE_EXIT_CODE
+            ))
+            .long("keep-going")
+            .action(ArgAction::SetTrue),
+    ]
+}
+
+pub fn explore_options(args: &ArgMatches) -> ExploreOptions {
+    let values = |id: &str| -> Vec<String> {
+        args.get_many::<String>(id)
+            .unwrap_or_default()
+            .cloned()
+            .collect()
+    };
+    ExploreOptions {
+        extensions: values("extension"),
+        include: values("include"),
+        exclude: values("exclude"),
+        hidden: args.get_one::<bool>("hidden").copied().unwrap(),
+        follow_symlinks: args.get_one::<bool>("follow-symlinks").copied().unwrap(),
+        max_depth: args.get_one::<usize>("max-depth").copied(),
+        no_gitignore: args.get_one::<bool>("no-gitignore").copied().unwrap(),
+        keep_going: args.get_one::<bool>("keep-going").copied().unwrap(),
+    }
+}
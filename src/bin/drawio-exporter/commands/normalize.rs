@@ -0,0 +1,32 @@
+use anyhow::Result;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use drawio_exporter::ops::normalize::{normalize, NormalizeOptions};
+
+use crate::commands::{explore_args, explore_options};
+
+pub fn cli() -> Command {
+    Command::new("normalize")
+        .about("Rewrite drawio files with uncompressed pages, for readable diffs")
+        .arg(
+            Arg::new("compressed")
+                .help("Compress the pages instead")
+                .long("compressed")
+                .action(ArgAction::SetTrue),
+        )
+        .args(explore_args())
+        .arg(
+            Arg::new("path")
+                .help("Path to the drawio files to normalize")
+                .value_name("PATH")
+                .default_value(".")
+                .index(1),
+        )
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    normalize(NormalizeOptions {
+        compressed: args.get_flag("compressed"),
+        explore_options: &explore_options(args),
+        path: args.get_one::<String>("path").unwrap(),
+    })
+}
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+use drawio_exporter::ops::split::{split, SplitOptions};
+
+use crate::commands::{explore_args, explore_options};
+
+pub fn cli() -> Command {
+    Command::new("split")
+        .about("Split multi-page drawio files into one file per page")
+        .arg(
+            Arg::new("folder")
+                .help(
+                    "Folder of the new files, next to the split files (the same folder by default)",
+                )
+                .value_name("folder")
+                .short('o')
+                .long("output"),
+        )
+        .args(explore_args())
+        .arg(
+            Arg::new("path")
+                .help("Path to the drawio files to split")
+                .value_name("PATH")
+                .default_value(".")
+                .index(1),
+        )
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    split(SplitOptions {
+        folder: args.get_one("folder"),
+        explore_options: &explore_options(args),
+        path: args.get_one::<String>("path").unwrap(),
+    })
+}
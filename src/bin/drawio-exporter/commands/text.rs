@@ -0,0 +1,27 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+use drawio_exporter::ops::text::{text, TextOptions};
+
+use crate::commands::{explore_args, explore_options, variable_arg, variables};
+
+pub fn cli() -> Command {
+    Command::new("text")
+        .about("Extract the text content of drawio files as a JSON search index")
+        .args(explore_args())
+        .arg(variable_arg())
+        .arg(
+            Arg::new("path")
+                .help("Path to the drawio files to explore")
+                .value_name("PATH")
+                .default_value(".")
+                .index(1),
+        )
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    text(TextOptions {
+        variables: &variables(args),
+        explore_options: &explore_options(args),
+        path: args.get_one::<String>("path").unwrap(),
+    })
+}
@@ -2,10 +2,19 @@
 extern crate clap;
 
 use anyhow::Result;
+use drawio_exporter::ops::{PartialFailure, PARTIAL_FAILURE_EXIT_CODE};
+use std::process;
 
 mod cli;
 mod commands;
 
 fn main() -> Result<()> {
-    cli::main()
+    match cli::main() {
+        // Partial failures use their own exit code, so they can be told apart from other errors
+        Err(error) if error.downcast_ref::<PartialFailure>().is_some() => {
+            eprintln!("Error: {:?}", error);
+            process::exit(PARTIAL_FAILURE_EXIT_CODE)
+        }
+        result => result,
+    }
 }
@@ -0,0 +1,136 @@
+// Discovery of the Draw.io Desktop application, when its path isn't given.
+//
+// The application is searched in this order:
+// - the path set in an environment variable
+// - the PATH folders
+// - the usual install locations (including flatpak and snap wrappers, and AppImage files)
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+use crate::core::drawio::drawio_desktop::{os_default_application, DrawioVersion};
+
+/// Environment variables which can set the path of the application
+pub const APPLICATION_ENV_VARIABLES: [&str; 2] = [
+    "DRAWIO_EXPORTER_APPLICATION",
+    "DRAWIO_DESKTOP_EXECUTABLE_PATH",
+];
+
+/// Find the Draw.io Desktop application
+pub fn discover_application() -> Result<PathBuf> {
+    for variable in APPLICATION_ENV_VARIABLES {
+        if let Some(application) = env::var_os(variable).filter(|value| !value.is_empty()) {
+            let application = PathBuf::from(application);
+            return match application.is_file() {
+                true => Ok(application),
+                false => Err(anyhow!(
+                    "Draw.io Desktop application path '{}' set by {} don't exists",
+                    application.display(),
+                    variable
+                )),
+            };
+        }
+    }
+
+    let locations = install_locations();
+    path_candidates()
+        .into_iter()
+        .chain(locations.iter().cloned())
+        .find(|candidate| candidate.is_file())
+        .ok_or_else(|| {
+            anyhow!(
+                "can't find Draw.io Desktop application in PATH, nor in {}, set its path with {} environment variable",
+                locations
+                    .iter()
+                    .map(|location| location.display().to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                APPLICATION_ENV_VARIABLES.join(" or ")
+            )
+        })
+}
+
+/// Names of the application, or of its wrappers, in the PATH folders
+fn path_candidates() -> Vec<PathBuf> {
+    let names: &[&str] = match env::consts::OS {
+        "windows" => &["draw.io.exe", "drawio.exe"],
+        _ => &["drawio", "draw.io"],
+    };
+    let paths = env::var_os("PATH").unwrap_or_default();
+    env::split_paths(&paths)
+        .flat_map(|folder| names.iter().map(move |name| folder.join(name)))
+        .collect()
+}
+
+/// Find a program by its path, or by its name in the PATH folders
+pub(crate) fn find_program(program: &str) -> Option<PathBuf> {
+    let path = Path::new(program);
+    if path.components().count() > 1 {
+        return Some(path.to_path_buf()).filter(|path| path.is_file());
+    }
+    let paths = env::var_os("PATH").unwrap_or_default();
+    env::split_paths(&paths)
+        .map(|folder| folder.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+fn install_locations() -> Vec<PathBuf> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let mut locations = vec![PathBuf::from(os_default_application())];
+    match env::consts::OS {
+        "linux" => {
+            locations.push(PathBuf::from("/usr/bin/drawio"));
+            locations.push(PathBuf::from("/usr/local/bin/drawio"));
+            locations.push(PathBuf::from("/snap/bin/drawio"));
+            locations.push(PathBuf::from(
+                "/var/lib/flatpak/exports/bin/com.jgraph.drawio.desktop",
+            ));
+            if let Some(home) = &home {
+                locations
+                    .push(home.join(".local/share/flatpak/exports/bin/com.jgraph.drawio.desktop"));
+                locations.extend(app_images(&home.join("Applications")));
+                locations.extend(app_images(&home.join(".local/bin")));
+            }
+            locations.extend(app_images(Path::new("/opt")));
+        }
+        "macos" => {
+            if let Some(home) = &home {
+                locations.push(home.join("Applications/draw.io.app/Contents/MacOS/draw.io"));
+            }
+        }
+        "windows" => {
+            if let Some(local_app_data) = env::var_os("LOCALAPPDATA") {
+                locations
+                    .push(PathBuf::from(local_app_data).join("Programs\\draw.io\\draw.io.exe"));
+            }
+        }
+        _ => {}
+    }
+    locations
+}
+
+/// AppImage files of draw.io in the folder, the most recent version first
+/// (their names are like `drawio-x86_64-24.7.5.AppImage`)
+fn app_images(folder: &Path) -> Vec<PathBuf> {
+    let mut app_images: Vec<PathBuf> = fs::read_dir(folder)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            name.starts_with("drawio") && name.ends_with(".appimage")
+        })
+        .collect();
+    app_images.sort_by_key(|path| {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        std::cmp::Reverse(DrawioVersion::parse(&name))
+    });
+    app_images
+}
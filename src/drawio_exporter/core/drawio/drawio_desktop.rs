@@ -1,61 +1,444 @@
 use anyhow::{anyhow, Context, Result};
 use predicate::str::contains;
 use predicates::prelude::*;
-use std::path::PathBuf;
-use std::process::Command;
+use std::cell::OnceCell;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::core::drawio::discovery::discover_application;
+use crate::core::drawio::export_backend::{ExportBackend, TransientExportError};
+use crate::core::drawio::virtual_display::{
+    prepare_display, probe_display_of, Display, DisplayMode, OFFSCREEN_ARGUMENTS,
+};
+use crate::core::interrupt::is_interrupted;
+
+pub struct DrawioDesktopOptions<'a> {
+    /// Path of the application, discovered when it's not set
+    pub application: Option<&'a String>,
+    pub is_headless: bool,
+    /// Kill draw.io when an execution takes longer
+    pub timeout: Option<Duration>,
+    pub display_mode: DisplayMode,
+    /// Xvfb program, by path or by name in the PATH folders
+    pub xvfb: &'a str,
+    /// Additional arguments of draw.io (checked with `check_drawio_argument`)
+    pub arguments: &'a [String],
+    /// Environment variables set for draw.io
+    pub environment: &'a [(String, String)],
+    /// Environment variables removed for draw.io
+    pub removed_environment: &'a [String],
+    /// Working directory of draw.io
+    pub current_dir: Option<&'a String>,
+}
+
+// Arguments set by drawio-exporter for every export, which can't be passed to draw.io as additional arguments
+// (the other ones are only rejected when the export sets them, like `--scale`)
+const EXPORT_ARGUMENTS: [&str; 5] = [
+    "--export",
+    "--output",
+    "--format",
+    "--all-pages",
+    "--page-index",
+];
+
+/// Check an additional argument of draw.io, formatted as `--name` or `--name=value`,
+/// which must not be one of the arguments set by drawio-exporter for every export
+pub fn check_drawio_argument(argument: &str) -> Result<()> {
+    let name = argument_name(argument);
+    if !name.starts_with("--") || name.len() == 2 {
+        anyhow::bail!(
+            "draw.io argument '{}' must be formatted as --name or --name=value",
+            argument
+        );
+    }
+    if EXPORT_ARGUMENTS.contains(&name) {
+        anyhow::bail!(
+            "draw.io argument '{}' is already set by drawio-exporter",
+            argument
+        );
+    }
+    Ok(())
+}
+
+/// Check that an additional argument of draw.io isn't set by drawio-exporter for the export
+fn check_drawio_argument_for_export(argument: &str, arguments: &ExportArguments<'_>) -> Result<()> {
+    check_drawio_argument(argument)?;
+    let name = argument_name(argument);
+    if arguments.as_shell_arguments().contains(&name) {
+        anyhow::bail!(
+            "draw.io argument '{}' is already set by drawio-exporter",
+            argument
+        );
+    }
+    Ok(())
+}
+
+fn argument_name(argument: &str) -> &str {
+    argument.split_once('=').map_or(argument, |(name, _)| name)
+}
 
 pub struct DrawioDesktop<'a> {
-    application: &'a str,
-    is_headless: bool,
+    options: DrawioDesktopOptions<'a>,
+    discovered_application: OnceCell<PathBuf>,
+    version: OnceCell<Option<DrawioVersion>>,
+    display: OnceCell<Display>,
 }
 
 impl<'a> DrawioDesktop<'a> {
-    pub fn new(application: &String, is_headless: bool) -> Result<DrawioDesktop<'_>> {
-        if !PathBuf::from(application).exists() {
-            return Err(anyhow!(format!(
-                "Draw.io Desktop application path '{}' don't exists",
-                application
-            )));
+    pub fn new(options: DrawioDesktopOptions<'a>) -> DrawioDesktop<'a> {
+        DrawioDesktop {
+            options,
+            discovered_application: OnceCell::new(),
+            version: OnceCell::new(),
+            display: OnceCell::new(),
         }
+    }
 
-        Ok(DrawioDesktop {
-            application,
-            is_headless,
-        })
+    /// Path of the application, as set or discovered
+    pub fn application(&self) -> Result<&Path> {
+        if let Some(application) = self.options.application {
+            return Ok(Path::new(application));
+        }
+        if let Some(application) = self.discovered_application.get() {
+            return Ok(application);
+        }
+        let application = discover_application()?;
+        Ok(self.discovered_application.get_or_init(|| application))
     }
 
-    pub fn execute(&self, arguments: ExportArguments<'a>) -> Result<()> {
+    /// Version of the application, from its `--version` output (if it can be read)
+    pub fn version(&self) -> Result<Option<DrawioVersion>> {
+        if let Some(version) = self.version.get() {
+            return Ok(*version);
+        }
+        // The display isn't prepared only to read the version (like starting Xvfb)
+        let probe_display;
+        let display = match self.display.get() {
+            Some(display) => display,
+            None => {
+                probe_display = probe_display_of(self.options.display_mode);
+                &probe_display
+            }
+        };
+        let version = match self.run(&["--version"], display) {
+            Ok((status, stdout, _)) if status.success() => {
+                DrawioVersion::parse(&String::from_utf8_lossy(&stdout))
+            }
+            _ => None,
+        };
+        Ok(*self.version.get_or_init(|| version))
+    }
+
+    /// Display used by the application, prepared on first use (like starting Xvfb)
+    pub fn display(&self) -> Result<&Display> {
+        if let Some(display) = self.display.get() {
+            return Ok(display);
+        }
+        let display = prepare_display(self.options.display_mode, self.options.xvfb)?;
+        match &display {
+            Display::Current => {}
+            Display::Offscreen => println!("+ use offscreen rendering of draw.io desktop"),
+            Display::Virtual(virtual_display) => {
+                println!("+ start virtual display : {}", virtual_display.display())
+            }
+        }
+        Ok(self.display.get_or_init(|| display))
+    }
+
+    pub fn execute(&self, arguments: &ExportArguments<'_>) -> Result<()> {
+        // Relative paths would be resolved from the working directory of draw.io
+        let (input, output) = match self.options.current_dir {
+            Some(_) => (
+                absolute_path(arguments.input)?,
+                arguments.output.map(absolute_path).transpose()?,
+            ),
+            None => (
+                arguments.input.to_string(),
+                arguments.output.map(str::to_string),
+            ),
+        };
+        let arguments = ExportArguments {
+            input: &input,
+            output: output.as_deref(),
+            ..*arguments
+        };
+
+        // Additional arguments are set before the input, which is always the last argument
         let mut shell_arguments = arguments.as_shell_arguments();
+        let input = shell_arguments.pop();
+        shell_arguments.extend(self.options.arguments.iter().map(String::as_str));
+        shell_arguments.extend(input);
+
+        let (status, stdout, stderr) = self.run(&shell_arguments, self.display()?)?;
+
+        if let Ok(command_output_string) = String::from_utf8(stdout) {
+            if !status.success() || contains("Error: ").eval(command_output_string.as_str()) {
+                let stderr = match String::from_utf8(stderr) {
+                    Ok(output) => output,
+                    Err(err) => format!("unreadable output due to {}", err),
+                };
+                return Err(TransientExportError::new(format!(
+                    "fail to export using draw.io desktop\n{}",
+                    stderr.as_str()
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
 
-        if self.is_headless {
+    /// Run the application, and return its exit status, and its outputs
+    fn run(&self, arguments: &[&str], display: &Display) -> Result<(ExitStatus, Vec<u8>, Vec<u8>)> {
+        let application = self.application()?;
+        // Electron arguments come first, so the input stays the last argument
+        let mut shell_arguments: Vec<&str> = vec![];
+
+        if self.options.is_headless {
             shell_arguments.push("--no-sandbox");
             shell_arguments.push("--disable-dev-shm-usage");
         }
 
-        let command_output = Command::new(self.application)
+        let mut command = Command::new(application);
+        match display {
+            Display::Current => {}
+            Display::Offscreen => shell_arguments.extend(OFFSCREEN_ARGUMENTS),
+            Display::Virtual(virtual_display) => {
+                virtual_display.check()?;
+                command.env("DISPLAY", virtual_display.display());
+            }
+        }
+        for name in self.options.removed_environment {
+            command.env_remove(name);
+        }
+        command.envs(
+            self.options
+                .environment
+                .iter()
+                .map(|(name, value)| (name, value)),
+        );
+        if let Some(current_dir) = self.options.current_dir {
+            command.current_dir(current_dir);
+        }
+        shell_arguments.extend(arguments);
+        command
             .args(&shell_arguments)
-            .output()
-            .with_context(|| {
-                format!(
-                    "failed to execute application command line {} {}",
-                    self.application,
-                    shell_arguments.join(" ")
-                )
-            })?;
-
-        if let Ok(command_output_string) = String::from_utf8(command_output.stdout.clone()) {
-            if !command_output.status.success()
-                || contains("Error: ").eval(command_output_string.as_str())
-            {
-                let stderr = match String::from_utf8(command_output.stderr) {
-                    Ok(output) => output,
-                    Err(err) => format!("unreadable output due to {}", err),
-                };
-                anyhow::bail!("fail to export using draw.io desktop\n{}", stderr.as_str());
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // draw.io starts several processes, so they are grouped to be killed together
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+
+        let mut child = command.spawn().with_context(|| {
+            format!(
+                "failed to execute application command line {} {}",
+                application.display(),
+                shell_arguments.join(" ")
+            )
+        })?;
+        let stdout = read_in_background(child.stdout.take());
+        let stderr = read_in_background(child.stderr.take());
+
+        let status = match wait_with_timeout(&mut child, self.options.timeout)? {
+            Waited::Exited(status) => status,
+            Waited::TimedOut(timeout) => {
+                kill_process_tree(&mut child);
+                return Err(TransientExportError::new(format!(
+                    "draw.io desktop didn't finish within {}s, its processes were killed",
+                    timeout.as_secs()
+                ))
+                .into());
+            }
+            Waited::Interrupted => {
+                kill_process_tree(&mut child);
+                anyhow::bail!("interrupted, the processes of draw.io desktop were killed");
+            }
+        };
+        Ok((status, join_output(stdout), join_output(stderr)))
+    }
+}
+
+/// Version of Draw.io Desktop, to know the options it supports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DrawioVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl DrawioVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> DrawioVersion {
+        DrawioVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Find the first version (like `24.7.5`) in the text
+    pub fn parse(text: &str) -> Option<DrawioVersion> {
+        text.split(|c: char| !c.is_ascii_digit() && c != '.')
+            .find_map(|word| {
+                let numbers = word
+                    .trim_matches('.')
+                    .split('.')
+                    .map(|number| number.parse::<u32>().ok())
+                    .collect::<Option<Vec<u32>>>()?;
+                match numbers.as_slice() {
+                    [major, minor, patch] => Some(DrawioVersion::new(*major, *minor, *patch)),
+                    _ => None,
+                }
+            })
+    }
+}
+
+impl fmt::Display for DrawioVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Options which need a recent version of Draw.io Desktop
+fn minimum_versions(arguments: &ExportArguments<'_>) -> Vec<(&'static str, DrawioVersion)> {
+    let mut minimum_versions = vec![];
+    if arguments.embed_diagram && arguments.format == "pdf" {
+        minimum_versions.push(("--embed-diagram (for pdf)", DrawioVersion::new(14, 5, 1)));
+    }
+    if arguments.embed_diagram && arguments.format == "svg" {
+        minimum_versions.push(("--embed-diagram (for svg)", DrawioVersion::new(16, 1, 2)));
+    }
+    if arguments.embed_svg_images {
+        minimum_versions.push(("--embed-svg-images", DrawioVersion::new(16, 1, 2)));
+    }
+    if arguments.enable_plugins {
+        minimum_versions.push(("--enable-plugins", DrawioVersion::new(19, 0, 3)));
+    }
+    minimum_versions
+}
+
+fn absolute_path(path: &str) -> Result<String> {
+    let absolute_path = std::path::absolute(path)
+        .with_context(|| format!("can't find the absolute path of {}", path))?;
+    Ok(absolute_path.to_string_lossy().into_owned())
+}
+
+/// Read the output while the process runs, so it never blocks on a full pipe
+fn read_in_background<R: Read + Send + 'static>(output: Option<R>) -> Option<JoinHandle<Vec<u8>>> {
+    output.map(|mut output| {
+        thread::spawn(move || {
+            let mut content = vec![];
+            let _ = output.read_to_end(&mut content);
+            content
+        })
+    })
+}
+
+fn join_output(output: Option<JoinHandle<Vec<u8>>>) -> Vec<u8> {
+    output
+        .and_then(|output| output.join().ok())
+        .unwrap_or_default()
+}
+
+enum Waited {
+    Exited(ExitStatus),
+    TimedOut(Duration),
+    Interrupted,
+}
+
+/// Wait for the process to exit, unless the timeout is reached or the command is interrupted
+fn wait_with_timeout(child: &mut Child, timeout: Option<Duration>) -> Result<Waited> {
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Waited::Exited(status));
+        }
+        if let Some(timeout) = timeout.filter(|timeout| started.elapsed() >= *timeout) {
+            return Ok(Waited::TimedOut(timeout));
+        }
+        if is_interrupted() {
+            return Ok(Waited::Interrupted);
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[cfg(unix)]
+fn kill_process_tree(child: &mut Child) {
+    // The process leads its own group, so killing the group also kills its children
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", child.id())])
+        .status();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(not(unix))]
+fn kill_process_tree(child: &mut Child) {
+    let _ = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &child.id().to_string()])
+        .status();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+impl ExportBackend for DrawioDesktop<'_> {
+    fn check(&self, arguments: &ExportArguments<'_>) -> Result<()> {
+        let application = self.application()?;
+        if !application.exists() {
+            return Err(anyhow!(format!(
+                "Draw.io Desktop application path '{}' don't exists",
+                application.display()
+            )));
+        }
+        for argument in self.options.arguments {
+            check_drawio_argument_for_export(argument, arguments)?;
+        }
+        if let Some(current_dir) = self.options.current_dir {
+            if !Path::new(current_dir).is_dir() {
+                anyhow::bail!(
+                    "working directory '{}' of draw.io desktop don't exists",
+                    current_dir
+                );
+            }
+        }
+
+        let minimum_versions = minimum_versions(arguments);
+        if minimum_versions.is_empty() {
+            return Ok(());
+        }
+        let version = match self.version()? {
+            Some(version) => version,
+            None => {
+                println!(
+                    "warn: can't detect the version of draw.io desktop, its support of the options isn't checked"
+                );
+                return Ok(());
+            }
+        };
+        for (option, minimum_version) in minimum_versions {
+            if version < minimum_version {
+                anyhow::bail!(
+                    "{} option needs draw.io desktop {} or later, but {} is installed",
+                    option,
+                    minimum_version,
+                    version
+                );
             }
         }
         Ok(())
     }
+
+    fn export(&self, arguments: &ExportArguments<'_>) -> Result<()> {
+        self.execute(arguments)
+    }
+
+    fn supports_batch(&self) -> bool {
+        true
+    }
 }
 
 pub fn os_default_application<'a>() -> &'a str {
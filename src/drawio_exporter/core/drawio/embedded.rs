@@ -0,0 +1,107 @@
+// draw.io can embed the diagram inside the exported image, so the image stays editable
+// (this is how the `.drawio.svg` and `.drawio.png` files of the VS Code integration work).
+
+use std::io::Read;
+
+use anyhow::{anyhow, Context, Result};
+use flate2::read::ZlibDecoder;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+// Keyword of the png text chunk holding the diagram
+const PNG_KEYWORD: &[u8] = b"mxfile";
+
+/// Extract the diagram from the `content` attribute of the svg root element
+pub fn from_svg(svg: &str) -> Result<String> {
+    // Only the root element is read, not the (possibly large) drawing
+    let mut reader = Reader::from_str(svg);
+    let content = loop {
+        match reader.read_event().context("can parse svg")? {
+            Event::Start(element) | Event::Empty(element) => {
+                break element
+                    .try_get_attribute("content")
+                    .context("can parse svg")?
+                    .map(|content| content.unescape_value().map(|value| value.into_owned()))
+                    .transpose()
+                    .context("can parse svg")?;
+            }
+            Event::Eof => break None,
+            _ => {}
+        }
+    };
+    content
+        .filter(|content| !content.is_empty())
+        .ok_or_else(|| anyhow!("no diagram embedded in svg"))
+}
+
+/// Extract the diagram from the `tEXt` or `zTXt` chunk of the png image
+pub fn from_png(png: &[u8]) -> Result<String> {
+    if !png.starts_with(&PNG_SIGNATURE) {
+        return Err(anyhow!("not a png image"));
+    }
+
+    let mut rest = &png[PNG_SIGNATURE.len()..];
+    // Each chunk is made of its length, its type, its data, and a crc
+    while rest.len() >= 12 {
+        let length = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        let chunk_type = &rest[4..8];
+        let data = rest
+            .get(8..8 + length)
+            .ok_or_else(|| anyhow!("truncated png chunk"))?;
+
+        if let Some(text) = match chunk_type {
+            b"tEXt" => text_chunk(data).map(|text| Ok(text.to_vec())),
+            b"zTXt" => compressed_text_chunk(data),
+            b"IEND" => break,
+            _ => None,
+        } {
+            let text = String::from_utf8(text?).context("can read png text chunk")?;
+            return Ok(decode_text(text));
+        }
+
+        rest = &rest[(12 + length).min(rest.len())..];
+    }
+
+    Err(anyhow!("no diagram embedded in png"))
+}
+
+fn text_chunk(data: &[u8]) -> Option<&[u8]> {
+    let (keyword, text) = split_keyword(data)?;
+    match keyword == PNG_KEYWORD {
+        true => Some(text),
+        false => None,
+    }
+}
+
+fn compressed_text_chunk(data: &[u8]) -> Option<Result<Vec<u8>>> {
+    let (keyword, compressed_text) = split_keyword(data)?;
+    if keyword != PNG_KEYWORD {
+        return None;
+    }
+    // The first byte is the compression method, only zlib (0) is defined
+    let compressed_text = compressed_text.get(1..)?;
+    let mut text = vec![];
+    Some(
+        ZlibDecoder::new(compressed_text)
+            .read_to_end(&mut text)
+            .map(|_| text)
+            .context("can uncompress png text chunk"),
+    )
+}
+
+fn split_keyword(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let separator = data.iter().position(|byte| *byte == 0)?;
+    Some((&data[..separator], &data[separator + 1..]))
+}
+
+fn decode_text(text: String) -> String {
+    // draw.io url-encodes the diagram to keep the chunk in latin-1
+    match text.trim_start().starts_with('<') {
+        true => text,
+        false => urlencoding::decode(&text)
+            .map(|decoded| decoded.into_owned())
+            .unwrap_or(text),
+    }
+}
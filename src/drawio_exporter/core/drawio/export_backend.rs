@@ -0,0 +1,264 @@
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::core::drawio::drawio_desktop::ExportArguments;
+use crate::core::drawio::mxfile::read_file;
+use crate::core::drawio::writer::{write_mxfile, WriteOptions};
+
+pub trait ExportBackend {
+    /// Check that the backend can be used with these arguments, before exporting any page
+    fn check(&self, _arguments: &ExportArguments<'_>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Export the pages of the input file selected by the arguments
+    fn export(&self, arguments: &ExportArguments<'_>) -> Result<()>;
+
+    /// Export all the pages with a single call, as one file per page in the output folder
+    /// (numbered like `diagram-1.png`)
+    fn supports_batch(&self) -> bool {
+        false
+    }
+}
+
+/// Failure of an export which may succeed when retried (like a timeout, or a crash of draw.io)
+#[derive(Debug)]
+pub struct TransientExportError {
+    message: String,
+}
+
+impl TransientExportError {
+    pub fn new(message: String) -> TransientExportError {
+        TransientExportError { message }
+    }
+
+    /// Check if the error, or one of its causes, is a transient failure
+    pub fn is_transient(error: &anyhow::Error) -> bool {
+        error
+            .chain()
+            .any(|cause| cause.downcast_ref::<TransientExportError>().is_some())
+    }
+}
+
+impl fmt::Display for TransientExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for TransientExportError {}
+
+// Formats supported by the export server
+const EXPORT_SERVER_FORMATS: [&str; 4] = ["jpg", "pdf", "png", "svg"];
+
+/// draw.io export server, exporting the pages sent with http requests
+/// (like the jgraph/export-server docker image)
+pub struct ExportServer {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl ExportServer {
+    pub fn new(url: &str, timeout: Option<Duration>) -> ExportServer {
+        let mut agent = ureq::AgentBuilder::new();
+        if let Some(timeout) = timeout {
+            agent = agent.timeout(timeout);
+        }
+        ExportServer {
+            url: url.to_string(),
+            agent: agent.build(),
+        }
+    }
+}
+
+impl ExportBackend for ExportServer {
+    fn check(&self, arguments: &ExportArguments<'_>) -> Result<()> {
+        if !EXPORT_SERVER_FORMATS.contains(&arguments.format) {
+            anyhow::bail!(
+                "{} format isn't supported by the export server",
+                arguments.format
+            );
+        }
+        Ok(())
+    }
+
+    fn export(&self, arguments: &ExportArguments<'_>) -> Result<()> {
+        self.check(arguments)?;
+        let output = arguments
+            .output
+            .ok_or_else(|| anyhow!("the export server needs an output file"))?;
+
+        // The server only reads the diagrams, not the images embedding them
+        let mxfile = read_file(Path::new(arguments.input))?;
+        let xml = write_mxfile(&mxfile, &WriteOptions::default())?;
+
+        let mut form: Vec<(&str, &str)> = vec![
+            ("format", arguments.format),
+            ("xml", &xml),
+            ("border", arguments.border),
+        ];
+        if let Some(page_index) = arguments.page_index {
+            form.push(("from", page_index));
+        }
+        // The export server numbers the pages from 0
+        let page_range = arguments
+            .page_range
+            .and_then(|page_range| page_range.split_once(".."))
+            .and_then(|(from, to)| Some((from.parse::<usize>().ok()?, to.parse::<usize>().ok()?)))
+            .map(|(from, to)| {
+                (
+                    from.saturating_sub(1).to_string(),
+                    to.saturating_sub(1).to_string(),
+                )
+            });
+        if let Some((from, to)) = &page_range {
+            form.push(("from", from));
+            form.push(("to", to));
+        }
+        if arguments.all_pages {
+            form.push(("allPages", "1"));
+        }
+        if let Some(scale) = arguments.scale {
+            form.push(("scale", scale));
+        }
+        if let Some(width) = arguments.width {
+            form.push(("w", width));
+        }
+        if let Some(height) = arguments.height {
+            form.push(("h", height));
+        }
+        if arguments.crop {
+            form.push(("crop", "1"));
+        }
+        if arguments.transparent {
+            form.push(("bg", "none"));
+        }
+        if arguments.embed_diagram {
+            form.push(("embedXml", "1"));
+        }
+        if arguments.embed_svg_images {
+            form.push(("embedImages", "1"));
+        }
+
+        let response = self.agent.post(&self.url).send_form(&form).map_err(|err| {
+            let message = format!(
+                "fail to export using the export server {}\n{}",
+                self.url, err
+            );
+            match err {
+                // The server can't handle the request as it is, no need to send it again
+                ureq::Error::Status(code, _) if code < 500 => anyhow!(message),
+                _ => TransientExportError::new(message).into(),
+            }
+        })?;
+        let mut content: Vec<u8> = vec![];
+        response
+            .into_reader()
+            .read_to_end(&mut content)
+            .with_context(|| format!("can read the export of {}", self.url))?;
+        fs::write(output, content).with_context(|| format!("can write {}", output))
+    }
+}
+
+/// Call of a fake backend, with the arguments of the export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportCall {
+    pub input: String,
+    pub output: Option<String>,
+    pub format: String,
+    pub page_index: Option<String>,
+    pub page_range: Option<String>,
+    pub all_pages: bool,
+}
+
+/// Backend recording its calls, and writing placeholder files instead of the exported pages
+/// (with the header and the end of their format, so they pass the verification of the exported files)
+#[derive(Debug, Default)]
+pub struct FakeBackend {
+    calls: Mutex<Vec<ExportCall>>,
+}
+
+impl FakeBackend {
+    pub fn new() -> FakeBackend {
+        FakeBackend::default()
+    }
+
+    /// Calls of the backend, in the order of the exports
+    pub fn calls(&self) -> Vec<ExportCall> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+impl ExportBackend for FakeBackend {
+    fn export(&self, arguments: &ExportArguments<'_>) -> Result<()> {
+        self.calls.lock().unwrap().push(ExportCall {
+            input: arguments.input.to_string(),
+            output: arguments.output.map(|output| output.to_string()),
+            format: arguments.format.to_string(),
+            page_index: arguments.page_index.cloned(),
+            page_range: arguments.page_range.cloned(),
+            all_pages: arguments.all_pages,
+        });
+        if let Some(output) = arguments.output {
+            fs::write(output, placeholder_file(arguments.format))
+                .with_context(|| format!("can write {}", output))?;
+        }
+        Ok(())
+    }
+}
+
+// An image of 1x1 pixel
+const PLACEHOLDER_PNG: [u8; 68] = [
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1f, 0x15, 0xc4,
+    0x89, 0x00, 0x00, 0x00, 0x0b, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0x60, 0x00, 0x02, 0x00,
+    0x00, 0x05, 0x00, 0x01, 0x7a, 0x5e, 0xab, 0x3f, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44,
+    0xae, 0x42, 0x60, 0x82,
+];
+
+// A blank page of 200x100 points
+const PLACEHOLDER_PDF: &str = concat!(
+    "%PDF-1.4\n",
+    "1 0 obj\n",
+    "<< /Type /Catalog /Pages 2 0 R >>\n",
+    "endobj\n",
+    "2 0 obj\n",
+    "<< /Type /Pages /Kids [3 0 R] /Count 1 /MediaBox [0 0 200 100] >>\n",
+    "endobj\n",
+    "3 0 obj\n",
+    "<< /Type /Page /Parent 2 0 R >>\n",
+    "endobj\n",
+    "xref\n",
+    "0 4\n",
+    "0000000000 65535 f \n",
+    "0000000009 00000 n \n",
+    "0000000058 00000 n \n",
+    "0000000139 00000 n \n",
+    "trailer\n",
+    "<< /Size 4 /Root 1 0 R >>\n",
+    "startxref\n",
+    "186\n",
+    "%%EOF\n",
+);
+
+fn placeholder_file(format: &str) -> Vec<u8> {
+    match format {
+        "png" => PLACEHOLDER_PNG.to_vec(),
+        // Start of image, frame of 1x1 pixel, and end of image
+        "jpg" => b"\xff\xd8\xff\xc0\x00\x0b\x08\x00\x01\x00\x01\x01\x01\x11\x00\xff\xd9".to_vec(),
+        "pdf" => PLACEHOLDER_PDF.as_bytes().to_vec(),
+        "svg" => {
+            b"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"1px\" height=\"1px\"/>".to_vec()
+        }
+        // Local file header, and end of central directory record
+        "vsdx" => [b"PK\x03\x04".as_slice(), b"PK\x05\x06", &[0; 18]].concat(),
+        "xml" => b"<mxfile/>".to_vec(),
+        format => format!("fake {}", format).into_bytes(),
+    }
+}
@@ -0,0 +1,216 @@
+// Verification of the files exported by draw.io.
+//
+// draw.io can exit successfully after writing an empty or a truncated file,
+// so the header and the end of the exported files are checked for their format.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::core::drawio::export_backend::TransientExportError;
+
+// Larger images are more likely a wrong value read from a damaged file
+const MAX_IMAGE_SIZE: u32 = 100_000;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const PNG_END: &[u8] = b"IEND\xae\x42\x60\x82";
+const JPG_START: &[u8] = b"\xff\xd8\xff";
+const JPG_END: &[u8] = b"\xff\xd9";
+const ZIP_START: &[u8] = b"PK\x03\x04";
+const ZIP_END_OF_CENTRAL_DIRECTORY: &[u8] = b"PK\x05\x06";
+
+/// Check that the exported file exists, and looks like a complete file of its format
+pub fn verify_exported_file(path: &Path, format: &str) -> Result<()> {
+    let invalid = |reason: String| -> anyhow::Error {
+        TransientExportError::new(format!(
+            "exported {} file {} {}",
+            format,
+            path.display(),
+            reason
+        ))
+        .into()
+    };
+
+    if !path.exists() {
+        return Err(invalid("is missing".to_string()));
+    }
+    let content = match fs::read(path) {
+        Ok(content) => content,
+        Err(err) => return Err(invalid(format!("can't be read: {}", err))),
+    };
+    if content.is_empty() {
+        return Err(invalid("is empty".to_string()));
+    }
+
+    let size = match format {
+        "png" => png_size(&content),
+        "jpg" => jpg_size(&content),
+        "svg" => svg_size(&content),
+        "pdf" => pdf(&content).map(|_| None),
+        "vsdx" => zip(&content).map(|_| None),
+        "xml" => xml_root(&content, "mxfile").map(|_| None),
+        _ => Ok(None),
+    }
+    .map_err(invalid)?;
+
+    match size {
+        Some((0, _)) | Some((_, 0)) => Err(invalid("has no width or no height".to_string())),
+        Some((width, height)) if width > MAX_IMAGE_SIZE || height > MAX_IMAGE_SIZE => Err(invalid(
+            format!("has an unlikely size of {}x{}", width, height),
+        )),
+        _ => Ok(()),
+    }
+}
+
+type Size = Option<(u32, u32)>;
+
+fn png_size(content: &[u8]) -> Result<Size, String> {
+    if !content.starts_with(PNG_SIGNATURE) || content.get(12..16) != Some(b"IHDR") {
+        return Err("doesn't start with a png header".to_string());
+    }
+    if !content.ends_with(PNG_END) {
+        return Err("is truncated (no png end chunk)".to_string());
+    }
+    Ok(Some((read_u32(content, 16), read_u32(content, 20))))
+}
+
+fn jpg_size(content: &[u8]) -> Result<Size, String> {
+    if !content.starts_with(JPG_START) {
+        return Err("doesn't start with a jpg header".to_string());
+    }
+    if !content.ends_with(JPG_END) {
+        return Err("is truncated (no jpg end marker)".to_string());
+    }
+
+    // The size is in the start of frame segment
+    let mut position = 2;
+    while position + 4 <= content.len() && content[position] == 0xff {
+        let marker = content[position + 1];
+        let length = u16::from_be_bytes([content[position + 2], content[position + 3]]) as usize;
+        let is_start_of_frame =
+            (0xc0..=0xcf).contains(&marker) && ![0xc4, 0xc8, 0xcc].contains(&marker);
+        if is_start_of_frame && position + 9 <= content.len() {
+            let height = u16::from_be_bytes([content[position + 5], content[position + 6]]);
+            let width = u16::from_be_bytes([content[position + 7], content[position + 8]]);
+            return Ok(Some((width as u32, height as u32)));
+        }
+        position += 2 + length;
+    }
+    Err("has no jpg frame".to_string())
+}
+
+fn svg_size(content: &[u8]) -> Result<Size, String> {
+    let root = xml_root(content, "svg")?;
+    let dimension = |name: &str| -> Option<f64> {
+        root.iter()
+            .find(|(attribute, _)| attribute == name)
+            .and_then(|(_, value)| leading_number(value))
+    };
+    // A size in percent or without unit can't be checked, only missing or null sizes are reported
+    Ok(match (dimension("width"), dimension("height")) {
+        (Some(width), Some(height)) => Some((width.ceil() as u32, height.ceil() as u32)),
+        _ => None,
+    })
+}
+
+fn pdf(content: &[u8]) -> Result<(), String> {
+    if !content.starts_with(b"%PDF-") {
+        return Err("doesn't start with a pdf header".to_string());
+    }
+    let end = &content[content.len().saturating_sub(1024)..];
+    if !contains(end, b"%%EOF") {
+        return Err("is truncated (no pdf end marker)".to_string());
+    }
+    Ok(())
+}
+
+fn zip(content: &[u8]) -> Result<(), String> {
+    if !content.starts_with(ZIP_START) {
+        return Err("doesn't start with a zip header".to_string());
+    }
+    // The end of central directory record is followed by a comment of 64KB at most
+    let end = &content[content.len().saturating_sub(65_535 + 22)..];
+    if !contains(end, ZIP_END_OF_CENTRAL_DIRECTORY) {
+        return Err("is truncated (no zip central directory)".to_string());
+    }
+    Ok(())
+}
+
+/// Read the whole xml document, and return the attributes of its root element
+fn xml_root(content: &[u8], expected_root: &str) -> Result<Vec<(String, String)>, String> {
+    let mut reader = Reader::from_reader(content);
+    let mut root: Option<Vec<(String, String)>> = None;
+    let mut depth = 0;
+    let mut buffer = vec![];
+    loop {
+        let event = reader
+            .read_event_into(&mut buffer)
+            .map_err(|err| format!("isn't a valid xml document: {}", err))?;
+        let is_start = matches!(event, Event::Start(_));
+        match event {
+            Event::Start(element) | Event::Empty(element) if root.is_none() => {
+                if element.name().as_ref() != expected_root.as_bytes() {
+                    return Err(format!(
+                        "has a {} root element instead of {}",
+                        String::from_utf8_lossy(element.name().as_ref()),
+                        expected_root
+                    ));
+                }
+                root = Some(attributes(&element));
+                if is_start {
+                    depth += 1;
+                }
+            }
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth -= 1,
+            Event::Eof => break,
+            _ => {}
+        }
+        buffer.clear();
+    }
+    match (root, depth) {
+        (None, _) => Err(format!("has no {} root element", expected_root)),
+        (Some(_), depth) if depth != 0 => Err("is truncated (unclosed xml elements)".to_string()),
+        (Some(root), _) => Ok(root),
+    }
+}
+
+fn attributes(element: &BytesStart<'_>) -> Vec<(String, String)> {
+    element
+        .attributes()
+        .flatten()
+        .map(|attribute| {
+            (
+                String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+                String::from_utf8_lossy(&attribute.value).into_owned(),
+            )
+        })
+        .collect()
+}
+
+fn leading_number(value: &str) -> Option<f64> {
+    let number: String = value
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    number.parse().ok()
+}
+
+fn read_u32(content: &[u8], position: usize) -> u32 {
+    u32::from_be_bytes([
+        content[position],
+        content[position + 1],
+        content[position + 2],
+        content[position + 3],
+    ])
+}
+
+fn contains(content: &[u8], pattern: &[u8]) -> bool {
+    content
+        .windows(pattern.len())
+        .any(|window| window == pattern)
+}
@@ -0,0 +1,308 @@
+// Labels of draw.io cells can contain a fragment of html (when the `html=1` style is used),
+// so we need to understand it to extract the links and the text of the labels.
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Anchor {
+    pub href: String,
+    pub text: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Text(String),
+    StartTag {
+        name: String,
+        attributes: Vec<(String, String)>,
+    },
+    EndTag {
+        name: String,
+    },
+}
+
+// Tags which break the text flow, so they act as a whitespace in the text content
+const BREAKING_TAGS: [&str; 15] = [
+    "br",
+    "div",
+    "p",
+    "li",
+    "ul",
+    "ol",
+    "tr",
+    "td",
+    "th",
+    "table",
+    "h1",
+    "h2",
+    "h3",
+    "hr",
+    "blockquote",
+];
+
+/// Extract all the anchors of the html fragment, with their cleaned text
+pub fn extract_anchors(html: &str) -> Vec<Anchor> {
+    let mut anchors: Vec<Anchor> = vec![];
+    let mut current_anchor: Option<(String, String)> = None;
+
+    for token in tokenize(html) {
+        match token {
+            Token::StartTag { name, attributes } if name == "a" => {
+                // Anchors can't be nested, so a new anchor closes the previous one
+                if let Some((href, text)) = current_anchor.take() {
+                    anchors.push(Anchor {
+                        href,
+                        text: normalize_whitespaces(&text),
+                    });
+                }
+                let href = attributes
+                    .into_iter()
+                    .find(|(name, _)| name == "href")
+                    .map(|(_, value)| value);
+                current_anchor = href.map(|href| (href, String::new()));
+            }
+            Token::EndTag { name } if name == "a" => {
+                if let Some((href, text)) = current_anchor.take() {
+                    anchors.push(Anchor {
+                        href,
+                        text: normalize_whitespaces(&text),
+                    });
+                }
+            }
+            Token::StartTag { name, .. } | Token::EndTag { name } => {
+                if let Some((_, text)) = current_anchor.as_mut() {
+                    if BREAKING_TAGS.contains(&name.as_str()) {
+                        text.push(' ');
+                    }
+                }
+            }
+            Token::Text(content) => {
+                if let Some((_, text)) = current_anchor.as_mut() {
+                    text.push_str(&content);
+                }
+            }
+        }
+    }
+
+    // An anchor without end tag ends with the fragment
+    if let Some((href, text)) = current_anchor {
+        anchors.push(Anchor {
+            href,
+            text: normalize_whitespaces(&text),
+        });
+    }
+
+    anchors
+}
+
+/// Extract the text of the html fragment, without any markup
+pub fn to_text(html: &str) -> String {
+    let mut text = String::new();
+    for token in tokenize(html) {
+        match token {
+            Token::Text(content) => text.push_str(&content),
+            Token::StartTag { name, .. } | Token::EndTag { name } => {
+                if BREAKING_TAGS.contains(&name.as_str()) {
+                    text.push(' ');
+                }
+            }
+        }
+    }
+    normalize_whitespaces(&text)
+}
+
+fn normalize_whitespaces(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = vec![];
+    let mut text = String::new();
+    let mut rest = html;
+
+    while let Some(position) = rest.find('<') {
+        text.push_str(&rest[..position]);
+        rest = &rest[position..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = match comment.find("-->") {
+                Some(end) => &comment[end + 3..],
+                None => "",
+            };
+            continue;
+        }
+
+        match parse_tag(rest) {
+            Some((token, length)) => {
+                if !text.is_empty() {
+                    tokens.push(Token::Text(decode_entities(&text)));
+                    text.clear();
+                }
+                tokens.push(token);
+                rest = &rest[length..];
+            }
+            None => {
+                // Not a tag, so it's a lonely '<' character
+                text.push('<');
+                rest = &rest[1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        tokens.push(Token::Text(decode_entities(&text)));
+    }
+
+    tokens
+}
+
+/// Parse a tag at the start of the input, and return it with its length
+fn parse_tag(input: &str) -> Option<(Token, usize)> {
+    // All the delimiters are ascii characters, so we can work on bytes
+    // without splitting any multi-bytes character
+    let bytes = input.as_bytes();
+    let mut index = 1;
+
+    let is_end_tag = bytes.get(index) == Some(&b'/');
+    if is_end_tag {
+        index += 1;
+    }
+
+    let name_start = index;
+    while index < bytes.len() && (bytes[index].is_ascii_alphanumeric() || bytes[index] == b'-') {
+        index += 1;
+    }
+    if index == name_start {
+        return None;
+    }
+    let name = input[name_start..index].to_ascii_lowercase();
+
+    let mut attributes: Vec<(String, String)> = vec![];
+    loop {
+        while index < bytes.len() && (bytes[index].is_ascii_whitespace() || bytes[index] == b'/') {
+            index += 1;
+        }
+        match bytes.get(index) {
+            None => return None,
+            Some(b'>') => {
+                index += 1;
+                break;
+            }
+            Some(_) => {}
+        }
+
+        let attribute_name_start = index;
+        while index < bytes.len()
+            && !bytes[index].is_ascii_whitespace()
+            && !b"=>/".contains(&bytes[index])
+        {
+            index += 1;
+        }
+        let attribute_name = input[attribute_name_start..index].to_ascii_lowercase();
+
+        while index < bytes.len() && bytes[index].is_ascii_whitespace() {
+            index += 1;
+        }
+        let mut attribute_value = "";
+        if bytes.get(index) == Some(&b'=') {
+            index += 1;
+            while index < bytes.len() && bytes[index].is_ascii_whitespace() {
+                index += 1;
+            }
+            match bytes.get(index) {
+                Some(&quote) if quote == b'"' || quote == b'\'' => {
+                    let value_start = index + 1;
+                    let value_length = input[value_start..].find(quote as char)?;
+                    attribute_value = &input[value_start..value_start + value_length];
+                    index = value_start + value_length + 1;
+                }
+                Some(_) => {
+                    let value_start = index;
+                    while index < bytes.len()
+                        && !bytes[index].is_ascii_whitespace()
+                        && bytes[index] != b'>'
+                    {
+                        index += 1;
+                    }
+                    attribute_value = &input[value_start..index];
+                }
+                None => return None,
+            }
+        }
+        if !attribute_name.is_empty() {
+            attributes.push((attribute_name, decode_entities(attribute_value)));
+        }
+    }
+
+    let token = match is_end_tag {
+        true => Token::EndTag { name },
+        false => Token::StartTag { name, attributes },
+    };
+    Some((token, index))
+}
+
+/// Decode the html entities (named, decimal, and hexadecimal ones)
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(position) = rest.find('&') {
+        decoded.push_str(&rest[..position]);
+        rest = &rest[position..];
+
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 32)
+            .map(|end| &rest[1..end + 1]);
+        match entity.and_then(decode_entity) {
+            Some(character) => {
+                decoded.push(character);
+                rest = &rest[entity.unwrap().len() + 2..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(number) = entity.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hexadecimal) => u32::from_str_radix(hexadecimal, 16).ok()?,
+            None => number.parse::<u32>().ok()?,
+        };
+        return char::from_u32(code);
+    }
+
+    let character = match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        // A non-breaking space is only a space in the text content
+        "nbsp" => ' ',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "hellip" => '…',
+        "mdash" => '—',
+        "ndash" => '–',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "bull" => '•',
+        "middot" => '·',
+        "deg" => '°',
+        "times" => '×',
+        "euro" => '€',
+        _ => return None,
+    };
+    Some(character)
+}
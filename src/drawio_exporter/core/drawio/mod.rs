@@ -1,2 +1,12 @@
+pub mod discovery;
 pub mod drawio_desktop;
+pub mod embedded;
+pub mod export_backend;
+pub mod exported_file;
+pub mod html;
 pub mod mxfile;
+pub mod pages;
+mod parser;
+pub mod placeholders;
+pub mod virtual_display;
+pub mod writer;
@@ -1,195 +1,565 @@
+use std::borrow::Cow;
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::Read;
+use std::ops::Range;
 use std::path::Path;
+use std::sync::Arc;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use base64::{engine::general_purpose, Engine as _};
 use flate2::read::DeflateDecoder;
-use regex::Regex;
-use serde::{Deserialize, Deserializer};
+use serde::Serialize;
 
-#[derive(Debug, Deserialize, PartialEq, Default, Clone)]
-pub struct MxCell {
-    pub value: Option<String>,
+use crate::core::drawio::embedded;
+use crate::core::drawio::html::{extract_anchors, to_text};
+use crate::core::drawio::parser::{parse_mx_graph_model, parse_mxfile};
+use crate::core::drawio::placeholders::{
+    builtin_variables, has_placeholders, replace_placeholders,
+};
+
+#[derive(Debug, Serialize, PartialEq, Clone)]
+pub struct Link {
+    pub url: String,
+    pub label: String,
+    pub kind: LinkKind,
 }
 
-impl MxCell {
-    pub fn get_link(&self) -> Option<(String, String)> {
-        if let Some(value) = self.value.clone() {
-            if value.contains("href=") {
-                return MxCell::extract_link(value);
-            }
+#[derive(Debug, Serialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    /// Link to an url or a file, outside of the drawio file
+    External,
+    /// Link to another page of the drawio file
+    Page,
+    /// Custom link running draw.io actions
+    Action,
+    /// Link with placeholders to substitute
+    Placeholder,
+}
+
+impl Link {
+    pub fn new(url: String, label: String) -> Link {
+        Link::with_placeholders(url, label, false)
+    }
+
+    /// Like draw.io, placeholders are only used when they are enabled on the shape
+    pub fn with_placeholders(url: String, label: String, placeholders: bool) -> Link {
+        let kind = if placeholders && has_placeholders(&url) {
+            LinkKind::Placeholder
+        } else if url.starts_with("data:page/id,") {
+            LinkKind::Page
+        } else if url.starts_with("data:action/json,") {
+            LinkKind::Action
+        } else {
+            LinkKind::External
+        };
+        Link { url, label, kind }
+    }
+
+    /// Id of the targeted page, for a page link
+    pub fn page_id(&self) -> Option<&str> {
+        match self.kind {
+            LinkKind::Page => self.url.strip_prefix("data:page/id,"),
+            _ => None,
         }
-        None
     }
+}
 
-    fn extract_link(value: String) -> Option<(String, String)> {
-        if let Ok(re) = Regex::new(".*href=\"(.*)\".*>(.*)<.*") {
-            if let Some(caps) = re.captures(value.as_str()) {
-                let link_url = caps.get(1).map(|link| link.as_str().to_string());
-                let link_label = caps.get(2).map(|link| link.as_str().to_string());
+impl LinkKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LinkKind::External => "external",
+            LinkKind::Page => "page",
+            LinkKind::Action => "action",
+            LinkKind::Placeholder => "placeholder",
+        }
+    }
+}
 
-                if let (Some(url), Some(label)) = (link_url, link_label) {
-                    return Some((url, cleanup_label(label)));
-                }
-            }
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct MxCell {
+    pub id: Option<String>,
+    pub value: Option<String>,
+    pub link: Option<String>,
+    pub parent: Option<String>,
+}
+
+impl MxCell {
+    pub fn get_links(&self) -> Vec<Link> {
+        let mut links: Vec<Link> = vec![];
+        if let Some(url) = self.link.clone() {
+            let label = self.value.as_deref().map(to_text).unwrap_or_default();
+            links.push(Link::new(url, label));
+        }
+        if let Some(value) = &self.value {
+            links.extend(extract_links(value, false));
         }
-        None
+        links
     }
 }
 
-#[derive(Debug, Deserialize, PartialEq, Default, Clone)]
+#[derive(Debug, PartialEq, Default, Clone)]
 pub struct UserObject {
+    pub id: Option<String>,
     pub label: Option<String>,
     pub link: Option<String>,
+    pub tooltip: Option<String>,
+    pub cell: Option<MxCell>,
+    /// Other attributes of the shape, like its custom properties
+    pub attributes: BTreeMap<String, String>,
 }
 
 impl UserObject {
-    pub fn get_link(&self) -> Option<(String, String)> {
-        if let Some(label) = self.label.clone() {
-            if let Some(url) = self.link.clone() {
-                return Some((url, cleanup_label(label)));
-            }
+    pub fn has_placeholders(&self) -> bool {
+        self.attributes.get("placeholders").map(|p| p.as_str()) == Some("1")
+    }
+
+    pub fn parent(&self) -> Option<&str> {
+        self.cell.as_ref().and_then(|cell| cell.parent.as_deref())
+    }
+
+    /// Value of a property of the shape
+    pub fn property(&self, name: &str) -> Option<&str> {
+        match name {
+            "id" => self.id.as_deref(),
+            "label" => self.label.as_deref(),
+            "link" => self.link.as_deref(),
+            "tooltip" => self.tooltip.as_deref(),
+            _ => self.attributes.get(name).map(|value| value.as_str()),
+        }
+    }
+
+    pub fn get_links(&self) -> Vec<Link> {
+        let placeholders = self.has_placeholders();
+        let mut links: Vec<Link> = vec![];
+        if let Some(url) = self.link.clone() {
+            // A link on the shape without any label is still a link (without label)
+            let label = self.label.as_deref().map(to_text).unwrap_or_default();
+            links.push(Link::with_placeholders(url, label, placeholders));
+        }
+        if let Some(label) = &self.label {
+            links.extend(extract_links(label, placeholders));
+        }
+        if let Some(tooltip) = &self.tooltip {
+            links.extend(extract_links(tooltip, placeholders));
         }
-        None
+        links
     }
 }
 
-fn cleanup_label(text: String) -> String {
-    let raw_label = text
-        .replace("&nbsp;", " ")
-        .replace("<br>", " ")
-        .replace("<b>", " ")
-        .replace("</b>", " ")
-        .replace("<u>", " ")
-        .replace("</u>", " ")
-        .replace("<i>", " ")
-        .replace("</i>", " ")
-        .replace("<strike>", " ")
-        .replace("</strike>", " ")
-        .replace("<span>", " ")
-        .replace("</span>", " ");
-    let trimmed_label = raw_label.as_str().trim();
-    let remove_multiple_whitespaces = Regex::new(r"\s+").unwrap();
-    remove_multiple_whitespaces
-        .replace_all(trimmed_label, " ")
-        .into_owned()
+fn extract_links(html: &str, placeholders: bool) -> Vec<Link> {
+    match html.contains('<') {
+        true => extract_anchors(html)
+            .into_iter()
+            .map(|anchor| Link::with_placeholders(anchor.href, anchor.text, placeholders))
+            .collect(),
+        false => vec![],
+    }
 }
 
-#[derive(Debug, Deserialize, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Element {
-    #[serde(rename = "mxCell")]
     MxCell(MxCell),
+    // draw.io uses both object and UserObject names for shapes with custom properties
     UserObject(UserObject),
-    #[serde(other, deserialize_with = "deserialize_ignore_any")]
     Other,
 }
 
-fn deserialize_ignore_any<'de, D: Deserializer<'de>>(deserializer: D) -> Result<(), D::Error> {
-    serde::de::IgnoredAny::deserialize(deserializer)?;
-    Ok(())
-}
-
-#[derive(Debug, Deserialize, PartialEq, Default, Clone)]
+#[derive(Debug, PartialEq, Default, Clone)]
 pub struct Root {
-    #[serde(rename = "$value")]
     pub elements: Vec<Element>,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Default, Clone)]
+#[derive(Debug, PartialEq, Default, Clone)]
 pub struct MxGraphModel {
-    #[serde(rename = "root", default)]
     pub root: Root,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Default, Clone)]
-pub struct Diagram {
-    pub id: String,
+#[derive(Debug, Serialize, PartialEq, Default, Clone)]
+pub struct TextContent {
+    pub labels: Vec<String>,
+    pub tooltips: Vec<String>,
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Default, Clone)]
+pub struct Attribute {
     pub name: String,
-    #[serde(rename = "mxGraphModel", default)]
-    pub mx_graph_model: MxGraphModel,
+    pub value: String,
 }
 
-impl Diagram {
-    pub fn get_links(&self) -> Vec<(String, String)> {
-        self.mx_graph_model
-            .root
-            .elements
+// Attributes used by draw.io itself, and not as custom properties
+const INTERNAL_ATTRIBUTES: [&str; 2] = ["placeholders", "tags"];
+
+impl MxGraphModel {
+    pub fn get_text_content(&self) -> TextContent {
+        let mut text_content = TextContent::default();
+        for element in &self.root.elements {
+            match element {
+                Element::MxCell(cell) => {
+                    if let Some(value) = cell.value.clone() {
+                        text_content.labels.push(to_text(&value));
+                    }
+                }
+                Element::UserObject(user_object) => {
+                    if let Some(label) = user_object.label.clone() {
+                        text_content.labels.push(to_text(&label));
+                    }
+                    if let Some(tooltip) = user_object.tooltip.clone() {
+                        text_content.tooltips.push(to_text(&tooltip));
+                    }
+                    for (name, value) in &user_object.attributes {
+                        if !INTERNAL_ATTRIBUTES.contains(&name.as_str()) {
+                            text_content.attributes.push(Attribute {
+                                name: name.clone(),
+                                value: value.clone(),
+                            });
+                        }
+                    }
+                }
+                Element::Other => {}
+            }
+        }
+        text_content.labels.retain(|label| !label.is_empty());
+        text_content.tooltips.retain(|tooltip| !tooltip.is_empty());
+        text_content
+    }
+
+    pub fn is_empty(&self) -> bool {
+        // The root cell and the layers (cells without parent or with the root cell as parent)
+        // are always present, even on an empty page
+        self.root.elements.iter().all(|element| match element {
+            Element::MxCell(cell) => {
+                cell.value.is_none() && matches!(cell.parent.as_deref(), None | Some("0"))
+            }
+            // The root cell holds the page properties when there are some
+            Element::UserObject(user_object) => {
+                user_object.label.is_none() && user_object.parent().is_none()
+            }
+            Element::Other => false,
+        })
+    }
+
+    /// Resolve the placeholders like draw.io, from the properties of the shape,
+    /// then from the ones of its ancestors (up to the root cell of the page),
+    /// and finally from the given variables
+    pub fn resolve_placeholders(&mut self, variables: &BTreeMap<String, String>) {
+        let elements = &self.root.elements;
+        let user_objects: BTreeMap<&str, &UserObject> = elements
             .iter()
             .filter_map(|element| match element {
-                Element::MxCell(cell) => cell.get_link(),
-                Element::UserObject(user_object) => user_object.get_link(),
+                Element::UserObject(user_object) => {
+                    user_object.id.as_deref().map(|id| (id, user_object))
+                }
+                _ => None,
+            })
+            .collect();
+        let parents: BTreeMap<&str, &str> = elements
+            .iter()
+            .filter_map(|element| match element {
+                Element::MxCell(cell) => cell.id.as_deref().zip(cell.parent.as_deref()),
+                Element::UserObject(user_object) => {
+                    user_object.id.as_deref().zip(user_object.parent())
+                }
                 Element::Other => None,
             })
+            .collect();
+
+        let resolve = |user_object: &UserObject, name: &str| -> Option<String> {
+            if let Some(value) = user_object.property(name) {
+                return Some(value.to_string());
+            }
+            let mut current = user_object.parent();
+            // The depth is limited to avoid an infinite loop on a malformed parents hierarchy
+            for _ in 0..parents.len() {
+                let id = current?;
+                if let Some(value) = user_objects.get(id).and_then(|o| o.property(name)) {
+                    return Some(value.to_string());
+                }
+                current = parents.get(id).copied();
+            }
+            None
+        };
+
+        let resolved: Vec<Option<UserObject>> = elements
+            .iter()
+            .map(|element| match element {
+                Element::UserObject(user_object) if user_object.has_placeholders() => {
+                    let replace = |text: &Option<String>| {
+                        text.as_deref().map(|text| {
+                            replace_placeholders(text, |name| {
+                                resolve(user_object, name).or_else(|| variables.get(name).cloned())
+                            })
+                        })
+                    };
+                    Some(UserObject {
+                        label: replace(&user_object.label),
+                        tooltip: replace(&user_object.tooltip),
+                        link: replace(&user_object.link),
+                        ..user_object.clone()
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+
+        for (element, resolved) in self.root.elements.iter_mut().zip(resolved) {
+            if let Some(user_object) = resolved {
+                *element = Element::UserObject(user_object);
+            }
+        }
+    }
+
+    pub fn get_links(&self) -> Vec<Link> {
+        self.root
+            .elements
+            .iter()
+            .flat_map(|element| match element {
+                Element::MxCell(cell) => cell.get_links(),
+                Element::UserObject(user_object) => user_object.get_links(),
+                Element::Other => vec![],
+            })
             .collect()
     }
 }
 
-#[derive(Debug, Deserialize, PartialEq, Clone)]
-pub struct Mxfile {
-    #[serde(rename = "diagram", default)]
-    pub diagrams: Vec<Diagram>,
+/// Content of a page, as found in the file
+#[derive(Debug, Default, Clone)]
+pub(crate) enum DiagramSource {
+    #[default]
+    Empty,
+    /// Uncompressed page, as a part of the content of the file
+    Plain {
+        content: Arc<str>,
+        range: Range<usize>,
+    },
+    /// Page compressed by draw.io
+    Compressed(String),
 }
 
-#[derive(Debug, Deserialize, PartialEq, Default, Clone)]
-pub struct CompressDiagram {
+/// Page of a drawio file, parsed only when its content is used
+#[derive(Debug, Default, Clone)]
+pub struct Diagram {
     pub id: String,
     pub name: String,
-    #[serde(rename = "$value")]
-    pub raw_diagram: String,
+    /// Other attributes of the page, kept to write the page back
+    attributes: Vec<(String, String)>,
+    source: DiagramSource,
+    mx_graph_model: OnceCell<MxGraphModel>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct MxfileWithCompressDiagrams {
-    #[serde(rename = "diagram", default)]
-    pub diagrams: Vec<CompressDiagram>,
-}
+impl Diagram {
+    pub(crate) fn new(attributes: Vec<(String, String)>, source: DiagramSource) -> Diagram {
+        let mut diagram = Diagram {
+            source,
+            ..Diagram::default()
+        };
+        for (name, value) in attributes {
+            match name.as_str() {
+                "id" => diagram.id = value,
+                "name" => diagram.name = value,
+                _ => diagram.attributes.push((name, value)),
+            }
+        }
+        diagram
+    }
 
-pub fn read_file(path: &Path) -> Result<Mxfile> {
-    let content = fs::read_to_string(path)
-        .with_context(|| format!("can read content of {}", path.display()))?;
-    match content.is_empty() {
-        true => Ok(Mxfile { diagrams: vec![] }),
-        false => parse_compressed_content(path, content.clone())
-            .or_else(|_| parse_uncompressed_content(path, content)),
+    /// Page with an already parsed content
+    pub fn from_model(id: String, name: String, mx_graph_model: MxGraphModel) -> Diagram {
+        Diagram {
+            id,
+            name,
+            attributes: vec![],
+            source: DiagramSource::Empty,
+            mx_graph_model: OnceCell::from(mx_graph_model),
+        }
+    }
+
+    pub(crate) fn source(&self) -> &DiagramSource {
+        &self.source
+    }
+
+    pub(crate) fn attributes(&self) -> &[(String, String)] {
+        &self.attributes
+    }
+
+    /// Same page, with another content
+    pub(crate) fn with_page_xml(&self, page_xml: String) -> Diagram {
+        let range = 0..page_xml.len();
+        Diagram {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            attributes: self.attributes.clone(),
+            source: DiagramSource::Plain {
+                content: Arc::from(page_xml),
+                range,
+            },
+            mx_graph_model: OnceCell::new(),
+        }
+    }
+
+    /// Xml of the page, as read from the file (uncompressed if needed)
+    pub(crate) fn page_xml(&self) -> Result<Option<Cow<'_, str>>> {
+        match &self.source {
+            DiagramSource::Empty => match self.mx_graph_model.get() {
+                // Only the pages read from a file can be written back
+                Some(mx_graph_model) if !mx_graph_model.root.elements.is_empty() => {
+                    Err(anyhow!("page {} has no xml to write back", self.name))
+                }
+                _ => Ok(None),
+            },
+            DiagramSource::Plain { content, range } => {
+                Ok(Some(Cow::Borrowed(&content[range.clone()])))
+            }
+            DiagramSource::Compressed(raw_diagram) => {
+                let xml_diagram = decompress_diagram(raw_diagram)
+                    .with_context(|| format!("can uncompress page {}", self.name))?;
+                Ok(Some(Cow::Owned(xml_diagram)))
+            }
+        }
+    }
+
+    /// Content of the page, parsed on the first use
+    pub fn mx_graph_model(&self) -> Result<&MxGraphModel> {
+        if let Some(mx_graph_model) = self.mx_graph_model.get() {
+            return Ok(mx_graph_model);
+        }
+        let mx_graph_model = self
+            .parse_source()
+            .with_context(|| format!("can parse page {}", self.name))?;
+        Ok(self.mx_graph_model.get_or_init(|| mx_graph_model))
+    }
+
+    pub fn mx_graph_model_mut(&mut self) -> Result<&mut MxGraphModel> {
+        self.mx_graph_model()?;
+        Ok(self
+            .mx_graph_model
+            .get_mut()
+            .expect("the page has just been parsed"))
     }
+
+    fn parse_source(&self) -> Result<MxGraphModel> {
+        match &self.source {
+            DiagramSource::Empty => Ok(MxGraphModel::default()),
+            DiagramSource::Plain { content, range } => {
+                parse_mx_graph_model(&content[range.clone()])
+            }
+            DiagramSource::Compressed(raw_diagram) => {
+                let xml_diagram = decompress_diagram(raw_diagram).context("can uncompress xml")?;
+                parse_mx_graph_model(&xml_diagram)
+            }
+        }
+    }
+
+    pub fn get_text_content(&self) -> Result<TextContent> {
+        Ok(self.mx_graph_model()?.get_text_content())
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.mx_graph_model()?.is_empty())
+    }
+
+    pub fn resolve_placeholders(&mut self, variables: &BTreeMap<String, String>) -> Result<()> {
+        self.mx_graph_model_mut()?.resolve_placeholders(variables);
+        Ok(())
+    }
+
+    pub fn get_links(&self) -> Result<Vec<Link>> {
+        Ok(self.mx_graph_model()?.get_links())
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Mxfile {
+    pub diagrams: Vec<Diagram>,
+    pub compressed: bool,
+    /// Attributes of the mxfile element, kept to write the file back
+    pub(crate) attributes: Vec<(String, String)>,
 }
 
-fn parse_compressed_content(path: &Path, content: String) -> Result<Mxfile> {
-    let mxfile_with_compressed_diagrams: MxfileWithCompressDiagrams =
-        serde_xml_rs::from_reader(content.as_bytes())
-            .with_context(|| format!("can parse xml on {}", path.display()))?;
-    let mxfile = decompress(mxfile_with_compressed_diagrams)
-        .with_context(|| format!("can uncompress xml on {}", path.display()))?;
-    Ok(mxfile)
+impl Mxfile {
+    /// Resolve the placeholders of all the pages, with the given variables
+    /// and the ones defined by draw.io (like the page name or the date)
+    pub fn resolve_placeholders(
+        &mut self,
+        filename: &str,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<()> {
+        let page_count = self.diagrams.len();
+        for (position, diagram) in self.diagrams.iter_mut().enumerate() {
+            let mut page_variables =
+                builtin_variables(filename, &diagram.name, position, page_count);
+            page_variables.extend(variables.clone());
+            diagram.resolve_placeholders(&page_variables)?;
+        }
+        Ok(())
+    }
 }
 
-fn decompress(mxfile_with_compressed_diagrams: MxfileWithCompressDiagrams) -> Result<Mxfile> {
-    let mut diagrams: Vec<Diagram> = vec![];
-    for compressed_diagram in mxfile_with_compressed_diagrams.diagrams {
-        let base64_raw_diagram =
-            general_purpose::STANDARD.decode(compressed_diagram.raw_diagram)?;
+pub fn read_file(path: &Path) -> Result<Mxfile> {
+    let data = fs::read(path).with_context(|| format!("can read content of {}", path.display()))?;
+    read_data(path, &data)
+}
 
-        let mut raw_diagram_deflate_decoder = DeflateDecoder::new(&base64_raw_diagram[..]);
-        let mut urlencoded_diagram = String::new();
-        raw_diagram_deflate_decoder.read_to_string(&mut urlencoded_diagram)?;
+/// Read the diagrams of a file, embedded or not in an image depending on its extension
+pub fn read_data(path: &Path, data: &[u8]) -> Result<Mxfile> {
+    let content = match file_extension(path).as_str() {
+        "svg" => {
+            let svg = String::from_utf8(data.to_vec())
+                .with_context(|| format!("can read content of {}", path.display()))?;
+            embedded::from_svg(&svg)
+                .with_context(|| format!("can extract diagram from {}", path.display()))?
+        }
+        "png" => embedded::from_png(data)
+            .with_context(|| format!("can extract diagram from {}", path.display()))?,
+        _ => String::from_utf8(data.to_vec())
+            .with_context(|| format!("can read content of {}", path.display()))?,
+    };
+    read_content(path, content)
+}
 
-        let xml_diagram = urlencoding::decode(urlencoded_diagram.as_str())?;
+/// Tell if the diagrams are embedded in an image, instead of being the content of the file
+pub fn is_embedded(path: &Path) -> bool {
+    matches!(file_extension(path).as_str(), "svg" | "png")
+}
 
-        let mx_graph_model: MxGraphModel = serde_xml_rs::from_reader(xml_diagram.as_bytes())?;
+fn file_extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
 
-        diagrams.push(Diagram {
-            id: compressed_diagram.id,
-            name: compressed_diagram.name,
-            mx_graph_model,
-        })
+/// Name of the file without its extension, like 'name' for 'name.drawio' or 'name.drawio.svg'
+pub fn drawio_file_stem(path: &Path) -> &str {
+    let file_stem = path
+        .file_stem()
+        .and_then(|file_stem| file_stem.to_str())
+        .unwrap_or_default();
+    match file_stem.rsplit_once('.') {
+        Some((name, "drawio")) if !name.is_empty() => name,
+        _ => file_stem,
     }
+}
 
-    Ok(Mxfile { diagrams })
+/// Read the pages of a drawio file, their content being parsed only when it's used
+pub fn read_content(path: &Path, content: String) -> Result<Mxfile> {
+    match content.is_empty() {
+        true => Ok(Mxfile::default()),
+        false => parse_mxfile(Arc::from(content))
+            .with_context(|| format!("can parse xml on {}", path.display())),
+    }
 }
 
-fn parse_uncompressed_content(path: &Path, content: String) -> Result<Mxfile> {
-    let mxfile: Mxfile = serde_xml_rs::from_reader(content.as_bytes())
-        .with_context(|| format!("can parse xml on {}", path.display()))?;
-    Ok(mxfile)
+/// Uncompress a page compressed by draw.io (url encoded, deflated, then base64 encoded)
+pub fn decompress_diagram(raw_diagram: &str) -> Result<String> {
+    let base64_raw_diagram = general_purpose::STANDARD.decode(raw_diagram)?;
+
+    let mut raw_diagram_deflate_decoder = DeflateDecoder::new(&base64_raw_diagram[..]);
+    let mut urlencoded_diagram = String::new();
+    raw_diagram_deflate_decoder.read_to_string(&mut urlencoded_diagram)?;
+
+    Ok(urlencoding::decode(urlencoded_diagram.as_str())?.into_owned())
 }
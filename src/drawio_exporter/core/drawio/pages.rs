@@ -0,0 +1,109 @@
+// Move pages between drawio files, keeping the page links working when possible.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Context, Result};
+
+use crate::core::drawio::mxfile::{Diagram, Mxfile};
+
+const PAGE_LINK_PREFIX: &str = "data:page/id,";
+
+/// One file per page, with the attributes of the original file
+pub fn split(mxfile: &Mxfile) -> Vec<Mxfile> {
+    mxfile
+        .diagrams
+        .iter()
+        .map(|diagram| Mxfile {
+            diagrams: vec![diagram.clone()],
+            compressed: mxfile.compressed,
+            attributes: single_page_attributes(mxfile),
+        })
+        .collect()
+}
+
+fn single_page_attributes(mxfile: &Mxfile) -> Vec<(String, String)> {
+    mxfile
+        .attributes
+        .iter()
+        .map(|(name, value)| match name.as_str() {
+            "pages" => (name.clone(), "1".to_string()),
+            _ => (name.clone(), value.clone()),
+        })
+        .collect()
+}
+
+/// All the pages in a single file, in the order of the files.
+/// A page id already used by a previous page is changed, like the page links targeting it.
+pub fn merge(mxfiles: &[Mxfile]) -> Result<Mxfile> {
+    let mut used_ids: BTreeSet<String> = BTreeSet::new();
+    let mut diagrams: Vec<Diagram> = vec![];
+    for mxfile in mxfiles {
+        let mut remapped_ids: BTreeMap<String, String> = BTreeMap::new();
+        for diagram in &mxfile.diagrams {
+            let mut id = diagram.id.clone();
+            let mut suffix = 1;
+            while used_ids.contains(&id) {
+                suffix += 1;
+                id = format!("{}-{}", diagram.id, suffix);
+            }
+            if id != diagram.id {
+                remapped_ids.insert(diagram.id.clone(), id.clone());
+            }
+            used_ids.insert(id);
+        }
+
+        for diagram in &mxfile.diagrams {
+            let mut merged_diagram = match remapped_ids.is_empty() {
+                true => diagram.clone(),
+                false => remap_page_links(diagram, &remapped_ids)
+                    .with_context(|| format!("can remap page links of page {}", diagram.name))?,
+            };
+            if let Some(id) = remapped_ids.get(&diagram.id) {
+                merged_diagram.id = id.clone();
+            }
+            diagrams.push(merged_diagram);
+        }
+    }
+
+    let compressed = !mxfiles.is_empty() && mxfiles.iter().all(|mxfile| mxfile.compressed);
+    let mut attributes: Vec<(String, String)> = match mxfiles.first() {
+        Some(mxfile) => mxfile.attributes.clone(),
+        None => vec![],
+    };
+    for (name, value) in attributes.iter_mut() {
+        if name == "pages" {
+            *value = diagrams.len().to_string();
+        }
+    }
+    Ok(Mxfile {
+        diagrams,
+        compressed,
+        attributes,
+    })
+}
+
+fn remap_page_links(diagram: &Diagram, remapped_ids: &BTreeMap<String, String>) -> Result<Diagram> {
+    match diagram.page_xml()? {
+        Some(page_xml) => Ok(diagram.with_page_xml(replace_page_links(&page_xml, remapped_ids))),
+        None => Ok(diagram.clone()),
+    }
+}
+
+/// Replace the targeted page ids, in the links of the shapes and in the links of their labels
+fn replace_page_links(xml: &str, remapped_ids: &BTreeMap<String, String>) -> String {
+    let mut replaced = String::with_capacity(xml.len());
+    let mut rest = xml;
+    while let Some(start) = rest.find(PAGE_LINK_PREFIX) {
+        let (before, after) = rest.split_at(start + PAGE_LINK_PREFIX.len());
+        replaced.push_str(before);
+        // Page ids generated by draw.io are made of letters, digits, '-' and '_'
+        let id_length = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(after.len());
+        let id = &after[..id_length];
+        replaced.push_str(remapped_ids.get(id).map(|id| id.as_str()).unwrap_or(id));
+        rest = &after[id_length..];
+    }
+    replaced.push_str(rest);
+    replaced
+}
@@ -0,0 +1,240 @@
+// Streaming parser of drawio files.
+//
+// The first pass only reads the structure of the file (the pages, and if they are compressed),
+// without building any cell, so the pages can be parsed later, only when they are needed.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::name::QName;
+use quick_xml::Reader;
+
+use crate::core::drawio::mxfile::{
+    Diagram, DiagramSource, Element, MxCell, MxGraphModel, Mxfile, Root, UserObject,
+};
+
+/// Read the pages of a drawio file, without parsing their content
+pub(crate) fn parse_mxfile(content: Arc<str>) -> Result<Mxfile> {
+    let mut reader = Reader::from_str(&content);
+    reader.config_mut().trim_text(true);
+
+    let mut diagrams: Vec<Diagram> = vec![];
+    let mut depth = 0;
+    let mut has_root = false;
+    let mut attributes: Vec<(String, String)> = vec![];
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) if depth == 1 && element.name().as_ref() == b"diagram" => {
+                let diagram_attributes = ordered_attributes(&element)?;
+                let source = read_diagram_source(&mut reader, &content).with_context(|| {
+                    let name = diagram_attributes.iter().find(|(name, _)| name == "name");
+                    format!(
+                        "can read page {}",
+                        name.map(|(_, value)| value.as_str()).unwrap_or_default()
+                    )
+                })?;
+                diagrams.push(Diagram::new(diagram_attributes, source));
+            }
+            Event::Empty(element) if depth == 1 && element.name().as_ref() == b"diagram" => {
+                let diagram_attributes = ordered_attributes(&element)?;
+                diagrams.push(Diagram::new(diagram_attributes, DiagramSource::Empty));
+            }
+            Event::Start(element) if depth == 0 => {
+                check_root(&element, has_root)?;
+                attributes = ordered_attributes(&element)?;
+                has_root = true;
+                depth += 1;
+            }
+            Event::Empty(element) if depth == 0 => {
+                check_root(&element, has_root)?;
+                attributes = ordered_attributes(&element)?;
+                has_root = true;
+            }
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth -= 1,
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    if !has_root || depth != 0 {
+        return Err(anyhow!("unexpected end of file"));
+    }
+
+    // A file without any diagram can't be considered as compressed
+    let compressed = !diagrams.is_empty()
+        && diagrams
+            .iter()
+            .all(|diagram| matches!(diagram.source(), DiagramSource::Compressed(_)));
+    Ok(Mxfile {
+        diagrams,
+        compressed,
+        attributes,
+    })
+}
+
+fn check_root(element: &BytesStart<'_>, has_root: bool) -> Result<()> {
+    match (element.name().as_ref(), has_root) {
+        (b"mxfile", false) => Ok(()),
+        (_, true) => Err(anyhow!("unexpected element after the mxfile element")),
+        (name, false) => Err(anyhow!(
+            "unexpected root element {}, instead of mxfile",
+            String::from_utf8_lossy(name)
+        )),
+    }
+}
+
+/// Find the content of the page, up to the end of the diagram element
+fn read_diagram_source(reader: &mut Reader<&[u8]>, content: &Arc<str>) -> Result<DiagramSource> {
+    let mut source = DiagramSource::Empty;
+    loop {
+        let start = buffer_position(reader);
+        match reader.read_event()? {
+            Event::Start(element) if element.name().as_ref() == b"mxGraphModel" => {
+                reader.read_to_end(QName(b"mxGraphModel"))?;
+                source = plain_source(content, start..buffer_position(reader));
+            }
+            Event::Empty(element) if element.name().as_ref() == b"mxGraphModel" => {
+                source = plain_source(content, start..buffer_position(reader));
+            }
+            Event::Text(text) => {
+                // Escaped blank characters can remain around the elements
+                let text = text.unescape()?;
+                if !text.trim().is_empty() {
+                    source = DiagramSource::Compressed(text.trim().to_string());
+                }
+            }
+            Event::Start(element) => {
+                reader.read_to_end(element.name())?;
+            }
+            Event::End(_) => return Ok(source),
+            Event::Eof => return Err(anyhow!("unexpected end of file")),
+            _ => {}
+        }
+    }
+}
+
+fn plain_source(content: &Arc<str>, range: Range<usize>) -> DiagramSource {
+    DiagramSource::Plain {
+        content: Arc::clone(content),
+        range,
+    }
+}
+
+fn buffer_position(reader: &Reader<&[u8]>) -> usize {
+    reader.buffer_position() as usize
+}
+
+/// Parse the cells of a page
+pub(crate) fn parse_mx_graph_model(xml: &str) -> Result<MxGraphModel> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut mx_graph_model = MxGraphModel::default();
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) if element.name().as_ref() == b"root" => {
+                mx_graph_model.root = parse_root(&mut reader)?;
+            }
+            Event::Eof => return Ok(mx_graph_model),
+            _ => {}
+        }
+    }
+}
+
+fn parse_root(reader: &mut Reader<&[u8]>) -> Result<Root> {
+    let mut root = Root::default();
+    loop {
+        let (element, has_children) = match reader.read_event()? {
+            Event::Start(element) => (element, true),
+            Event::Empty(element) => (element, false),
+            Event::End(_) => return Ok(root),
+            Event::Eof => return Err(anyhow!("unexpected end of page")),
+            _ => continue,
+        };
+
+        let parsed_element = match element.name().as_ref() {
+            b"mxCell" => {
+                if has_children {
+                    reader.read_to_end(element.name())?;
+                }
+                Element::MxCell(mx_cell(&element)?)
+            }
+            // draw.io uses both names for shapes with custom properties
+            b"UserObject" | b"object" => {
+                let mut user_object = user_object(&element)?;
+                if has_children {
+                    user_object.cell = user_object_cell(reader)?;
+                }
+                Element::UserObject(user_object)
+            }
+            _ => {
+                if has_children {
+                    reader.read_to_end(element.name())?;
+                }
+                Element::Other
+            }
+        };
+        root.elements.push(parsed_element);
+    }
+}
+
+/// The cell of a shape with custom properties is its first child
+fn user_object_cell(reader: &mut Reader<&[u8]>) -> Result<Option<MxCell>> {
+    let mut cell: Option<MxCell> = None;
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) => {
+                if cell.is_none() && element.name().as_ref() == b"mxCell" {
+                    cell = Some(mx_cell(&element)?);
+                }
+                reader.read_to_end(element.name())?;
+            }
+            Event::Empty(element) if cell.is_none() && element.name().as_ref() == b"mxCell" => {
+                cell = Some(mx_cell(&element)?);
+            }
+            Event::End(_) => return Ok(cell),
+            Event::Eof => return Err(anyhow!("unexpected end of page")),
+            _ => {}
+        }
+    }
+}
+
+fn mx_cell(element: &BytesStart<'_>) -> Result<MxCell> {
+    let mut attributes = attributes(element)?;
+    Ok(MxCell {
+        id: attributes.remove("id"),
+        value: attributes.remove("value"),
+        link: attributes.remove("link"),
+        parent: attributes.remove("parent"),
+    })
+}
+
+fn user_object(element: &BytesStart<'_>) -> Result<UserObject> {
+    let mut attributes = attributes(element)?;
+    Ok(UserObject {
+        id: attributes.remove("id"),
+        label: attributes.remove("label"),
+        link: attributes.remove("link"),
+        tooltip: attributes.remove("tooltip"),
+        cell: None,
+        attributes,
+    })
+}
+
+fn attributes(element: &BytesStart<'_>) -> Result<BTreeMap<String, String>> {
+    Ok(ordered_attributes(element)?.into_iter().collect())
+}
+
+/// Attributes in the order of the file, to write them back the same way
+fn ordered_attributes(element: &BytesStart<'_>) -> Result<Vec<(String, String)>> {
+    let mut attributes: Vec<(String, String)> = vec![];
+    for attribute in element.attributes() {
+        let attribute = attribute?;
+        let name = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+        attributes.push((name, attribute.unescape_value()?.into_owned()));
+    }
+    Ok(attributes)
+}
@@ -0,0 +1,130 @@
+// Shapes with the `placeholders=1` property can use `%name%` placeholders in their label,
+// tooltip, and link, which draw.io replaces by the values of the properties with the same name.
+
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Placeholders which keep their meaning, even on shapes using placeholders
+const RESERVED_PLACEHOLDERS: [&str; 2] = ["label", "tooltip"];
+
+/// Replace every `%name%` placeholder of the text which can be resolved,
+/// and keep the other ones as they are
+pub fn replace_placeholders<F>(text: &str, resolve: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut replaced = String::with_capacity(text.len());
+
+    scan_placeholders(text, |part| match part {
+        Part::Text(text) => replaced.push_str(text),
+        Part::Placeholder(name) => {
+            let value = match RESERVED_PLACEHOLDERS.contains(&name) {
+                true => None,
+                false => resolve(name),
+            };
+            match value {
+                Some(value) => replaced.push_str(&value),
+                None => {
+                    replaced.push('%');
+                    replaced.push_str(name);
+                    replaced.push('%');
+                }
+            }
+        }
+    });
+
+    replaced
+}
+
+/// Whether the text has a `%name%` placeholder which can be replaced
+pub fn has_placeholders(text: &str) -> bool {
+    let mut found = false;
+    scan_placeholders(
+        text,
+        |part| {
+            found |=
+                matches!(part, Part::Placeholder(name) if !RESERVED_PLACEHOLDERS.contains(&name))
+        },
+    );
+    found
+}
+
+enum Part<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Split the text into the placeholders and the text around them
+fn scan_placeholders<'a, F>(text: &'a str, mut on_part: F)
+where
+    F: FnMut(Part<'a>),
+{
+    let mut rest = text;
+
+    while let Some(start) = rest.find('%') {
+        let end = match rest[start + 1..].find('%') {
+            Some(end) => start + 1 + end,
+            // A lonely '%' character
+            None => break,
+        };
+        let name = &rest[start + 1..end];
+        if is_placeholder_name(name) {
+            on_part(Part::Text(&rest[..start]));
+            on_part(Part::Placeholder(name));
+            rest = &rest[end + 1..];
+        } else {
+            // Like in "50% off %version%", the first '%' is a literal one,
+            // and the second one may start a placeholder
+            on_part(Part::Text(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+    on_part(Part::Text(rest));
+}
+
+/// A placeholder name is a non-empty text without blank characters
+fn is_placeholder_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(char::is_whitespace)
+}
+
+/// Variables defined by draw.io for each page
+pub fn builtin_variables(
+    filename: &str,
+    page: &str,
+    position: usize,
+    page_count: usize,
+) -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("filename".to_string(), filename.to_string()),
+        ("page".to_string(), page.to_string()),
+        ("pagenumber".to_string(), (position + 1).to_string()),
+        ("pagecount".to_string(), page_count.to_string()),
+        ("date".to_string(), today()),
+    ])
+}
+
+/// Current date, formatted as YYYY-MM-DD
+fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() / 86_400)
+        .unwrap_or_default() as i64;
+
+    // Convert the days since 1970-01-01 to a civil date (Howard Hinnant's algorithm)
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
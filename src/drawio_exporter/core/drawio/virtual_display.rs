@@ -0,0 +1,159 @@
+// Display of draw.io on Linux servers.
+//
+// Electron needs a display to start, so when none is set, draw.io runs on a Xvfb server
+// started for the export, or with the headless platform of Electron when Xvfb isn't installed.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::core::drawio::discovery::find_program;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayMode {
+    /// Use a virtual display only on Linux without display, with Xvfb when it's installed
+    Auto,
+    Xvfb,
+    Offscreen,
+    /// Use the current display
+    Off,
+}
+
+/// Electron arguments to render the pages without any display
+pub const OFFSCREEN_ARGUMENTS: [&str; 2] = ["--ozone-platform=headless", "--disable-gpu"];
+
+// Display numbers tried for Xvfb, away from the ones of the usual X servers
+const XVFB_DISPLAY_NUMBERS: std::ops::Range<u32> = 99..199;
+const XVFB_START_TIMEOUT: Duration = Duration::from_secs(10);
+const XVFB_STOP_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub enum Display {
+    Current,
+    Offscreen,
+    Virtual(VirtualDisplay),
+}
+
+/// Choose the display of draw.io, starting Xvfb when needed
+pub fn prepare_display(mode: DisplayMode, xvfb: &str) -> Result<Display> {
+    match mode {
+        DisplayMode::Off => Ok(Display::Current),
+        DisplayMode::Offscreen => Ok(Display::Offscreen),
+        DisplayMode::Xvfb => {
+            let xvfb = find_program(xvfb).ok_or_else(|| anyhow!("can't find Xvfb ({})", xvfb))?;
+            Ok(Display::Virtual(VirtualDisplay::start(&xvfb)?))
+        }
+        DisplayMode::Auto => {
+            if env::consts::OS != "linux" || has_display() {
+                return Ok(Display::Current);
+            }
+            match find_program(xvfb) {
+                Some(xvfb) => Ok(Display::Virtual(VirtualDisplay::start(&xvfb)?)),
+                None => Ok(Display::Offscreen),
+            }
+        }
+    }
+}
+
+/// Choose the display of a short run of draw.io (like reading its version), without starting Xvfb
+pub fn probe_display_of(mode: DisplayMode) -> Display {
+    match mode {
+        DisplayMode::Off => Display::Current,
+        DisplayMode::Auto if env::consts::OS != "linux" || has_display() => Display::Current,
+        _ => Display::Offscreen,
+    }
+}
+
+fn has_display() -> bool {
+    ["DISPLAY", "WAYLAND_DISPLAY"]
+        .iter()
+        .any(|variable| env::var_os(variable).is_some_and(|value| !value.is_empty()))
+}
+
+/// Xvfb server, stopped when it's dropped
+pub struct VirtualDisplay {
+    display: String,
+    xvfb: Mutex<Child>,
+}
+
+impl VirtualDisplay {
+    /// Start Xvfb on a free display, and wait for it to be ready
+    pub fn start(xvfb: &Path) -> Result<VirtualDisplay> {
+        let number = XVFB_DISPLAY_NUMBERS
+            .clone()
+            .find(|number| !display_files(*number).iter().any(|file| file.exists()))
+            .ok_or_else(|| anyhow!("can't find a free display for Xvfb"))?;
+        let display = format!(":{}", number);
+
+        let child = Command::new(xvfb)
+            .args([&display, "-screen", "0", "1920x1080x24", "-nolisten", "tcp"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("failed to start {}", xvfb.display()))?;
+        let virtual_display = VirtualDisplay {
+            display,
+            xvfb: Mutex::new(child),
+        };
+
+        // The server is ready when its socket is created
+        let socket = &display_files(number)[1];
+        let deadline = Instant::now() + XVFB_START_TIMEOUT;
+        while !socket.exists() {
+            virtual_display.check()?;
+            if Instant::now() >= deadline {
+                anyhow::bail!(
+                    "Xvfb isn't ready on display {} after {}s",
+                    virtual_display.display,
+                    XVFB_START_TIMEOUT.as_secs()
+                );
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        Ok(virtual_display)
+    }
+
+    /// Value of the DISPLAY variable to use the virtual display
+    pub fn display(&self) -> &str {
+        &self.display
+    }
+
+    /// Check that Xvfb is still running
+    pub fn check(&self) -> Result<()> {
+        match self.xvfb.lock().unwrap().try_wait()? {
+            Some(status) => Err(anyhow!(
+                "Xvfb of display {} stopped with {}",
+                self.display,
+                status
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for VirtualDisplay {
+    fn drop(&mut self) {
+        let xvfb = self.xvfb.get_mut().unwrap();
+        // Xvfb removes its lock and socket files when it's terminated
+        #[cfg(unix)]
+        let _ = Command::new("kill").arg(xvfb.id().to_string()).status();
+        let deadline = Instant::now() + XVFB_STOP_TIMEOUT;
+        while matches!(xvfb.try_wait(), Ok(None)) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(50));
+        }
+        let _ = xvfb.kill();
+        let _ = xvfb.wait();
+    }
+}
+
+/// Lock and socket files of a X server display
+fn display_files(number: u32) -> [PathBuf; 2] {
+    [
+        PathBuf::from(format!("/tmp/.X{}-lock", number)),
+        PathBuf::from(format!("/tmp/.X11-unix/X{}", number)),
+    ]
+}
@@ -0,0 +1,175 @@
+// Write drawio files back, with compressed or uncompressed pages.
+//
+// The pages are written from their xml as read in the file (not from the parsed cells),
+// so nothing is lost (like the geometry or the style of the shapes).
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use quick_xml::events::attributes::Attribute;
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+
+use crate::core::drawio::mxfile::{Diagram, DiagramSource, Mxfile};
+
+/// Compress a page like draw.io (url encoded, deflated, then base64 encoded)
+pub fn compress_diagram(xml_diagram: &str) -> Result<String> {
+    let urlencoded_diagram = urlencoding::encode(xml_diagram);
+
+    let mut raw_diagram_deflate_encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    raw_diagram_deflate_encoder.write_all(urlencoded_diagram.as_bytes())?;
+    let raw_diagram = raw_diagram_deflate_encoder.finish()?;
+
+    Ok(general_purpose::STANDARD.encode(raw_diagram))
+}
+
+// Attributes of the mxfile element updated by draw.io on each save
+pub const VOLATILE_ATTRIBUTES: [&str; 5] = ["agent", "etag", "host", "modified", "version"];
+
+// Attributes of the pages updated by draw.io when the view is scrolled
+pub const VOLATILE_PAGE_ATTRIBUTES: [&str; 2] = ["dx", "dy"];
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WriteOptions {
+    /// Compress the pages, instead of writing them uncompressed
+    pub compressed: bool,
+    /// Remove the volatile attributes, and sort the attributes of all the elements by name
+    pub formatted: bool,
+}
+
+pub fn write_file(path: &Path, mxfile: &Mxfile, options: &WriteOptions) -> Result<()> {
+    let content = write_mxfile(mxfile, options)?;
+    fs::write(path, content).with_context(|| format!("can write {}", path.display()))
+}
+
+/// Serialize the pages, indented to be readable when they are uncompressed
+pub fn write_mxfile(mxfile: &Mxfile, options: &WriteOptions) -> Result<String> {
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+
+    let mut mxfile_attributes: Vec<(&str, String)> = vec![];
+    for (name, value) in &mxfile.attributes {
+        match name.as_str() {
+            // draw.io tells how the pages are stored, so it must stay consistent
+            "compressed" => mxfile_attributes.push(("compressed", options.compressed.to_string())),
+            name if options.formatted && VOLATILE_ATTRIBUTES.contains(&name) => {}
+            name => mxfile_attributes.push((name, value.clone())),
+        }
+    }
+    if options.formatted {
+        mxfile_attributes.sort();
+    }
+    let mut mxfile_element = BytesStart::new("mxfile");
+    for (name, value) in &mxfile_attributes {
+        mxfile_element.push_attribute((*name, value.as_str()));
+    }
+    writer.write_event(Event::Start(mxfile_element))?;
+
+    for diagram in &mxfile.diagrams {
+        write_diagram(&mut writer, diagram, options)
+            .with_context(|| format!("can write page {}", diagram.name))?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::new("mxfile")))?;
+
+    let mut content = String::from_utf8(writer.into_inner())?;
+    content.push('\n');
+    Ok(content)
+}
+
+fn write_diagram(
+    writer: &mut Writer<Vec<u8>>,
+    diagram: &Diagram,
+    options: &WriteOptions,
+) -> Result<()> {
+    let mut diagram_attributes: Vec<(&str, &str)> =
+        vec![("id", diagram.id.as_str()), ("name", diagram.name.as_str())];
+    for (name, value) in diagram.attributes() {
+        diagram_attributes.push((name.as_str(), value.as_str()));
+    }
+    if options.formatted {
+        diagram_attributes.sort();
+    }
+    let mut diagram_element = BytesStart::new("diagram");
+    diagram_element.extend_attributes(diagram_attributes);
+
+    let page_xml = match diagram.page_xml()? {
+        Some(page_xml) => page_xml,
+        None => {
+            writer.write_event(Event::Empty(diagram_element))?;
+            return Ok(());
+        }
+    };
+
+    writer.write_event(Event::Start(diagram_element))?;
+    match (options.compressed, diagram.source()) {
+        // Already compressed pages are kept as they are, when they aren't formatted
+        (true, DiagramSource::Compressed(raw_diagram)) if !options.formatted => {
+            writer.write_event(Event::Text(BytesText::new(raw_diagram)))?;
+        }
+        (true, _) => {
+            let mut compact_writer = Writer::new(Vec::new());
+            copy_events(&page_xml, &mut compact_writer, options.formatted)?;
+            let xml_diagram = String::from_utf8(compact_writer.into_inner())?;
+            let raw_diagram = compress_diagram(&xml_diagram)?;
+            writer.write_event(Event::Text(BytesText::new(&raw_diagram)))?;
+        }
+        (false, _) => copy_events(&page_xml, writer, options.formatted)?,
+    }
+    writer.write_event(Event::End(BytesEnd::new("diagram")))?;
+    Ok(())
+}
+
+/// Xml of the page written like in a formatted file, without the blank characters between the elements,
+/// so the pages with the same content have the same xml (whatever their storage)
+pub fn normalized_page_xml(diagram: &Diagram) -> Result<Option<String>> {
+    let page_xml = match diagram.page_xml()? {
+        Some(page_xml) => page_xml,
+        None => return Ok(None),
+    };
+    let mut writer = Writer::new(Vec::new());
+    copy_events(&page_xml, &mut writer, true)?;
+    Ok(Some(String::from_utf8(writer.into_inner())?))
+}
+
+/// Copy the elements of the page, without the blank characters between them
+fn copy_events(xml: &str, writer: &mut Writer<Vec<u8>>, formatted: bool) -> Result<()> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+    loop {
+        match reader.read_event()? {
+            Event::Eof => return Ok(()),
+            Event::Decl(_) => {}
+            Event::Start(element) if formatted => {
+                writer.write_event(Event::Start(format_element(&element)?))?
+            }
+            Event::Empty(element) if formatted => {
+                writer.write_event(Event::Empty(format_element(&element)?))?
+            }
+            event => writer.write_event(event)?,
+        }
+    }
+}
+
+/// Sort the attributes by name, keeping their values as they are written,
+/// and remove the volatile attributes of the page
+fn format_element<'a>(element: &'a BytesStart<'a>) -> Result<BytesStart<'a>> {
+    let is_page = element.name().as_ref() == b"mxGraphModel";
+    let mut attributes = element
+        .attributes()
+        .collect::<std::result::Result<Vec<Attribute<'_>>, _>>()?;
+    attributes.retain(|attribute| {
+        !(is_page
+            && VOLATILE_PAGE_ATTRIBUTES
+                .iter()
+                .any(|name| name.as_bytes() == attribute.key.as_ref()))
+    });
+    attributes.sort_by(|a, b| a.key.as_ref().cmp(b.key.as_ref()));
+
+    let name = String::from_utf8(element.name().as_ref().to_vec())?;
+    Ok(BytesStart::new(name).with_attributes(attributes))
+}
@@ -1,4 +1,7 @@
-use crate::core::drawio::mxfile::{read_file, Mxfile};
+use crate::core::drawio::mxfile::{drawio_file_stem, Mxfile};
+use crate::core::explorer::{
+    has_extension, read_files, ExploreOptions, ExploredFiles, IGNORE_FILENAME,
+};
 use anyhow::Result;
 use ignore::WalkBuilder;
 use std::fs;
@@ -9,6 +12,7 @@ use std::time::SystemTime;
 pub struct FilterOptions<'a> {
     on_changes: bool,
     folder: &'a str,
+    explore_options: ExploreOptions,
 }
 
 impl<'a> FilterOptions<'a> {
@@ -16,6 +20,7 @@ impl<'a> FilterOptions<'a> {
         FilterOptions {
             on_changes: false,
             folder: "",
+            explore_options: ExploreOptions::default(),
         }
     }
 
@@ -23,42 +28,46 @@ impl<'a> FilterOptions<'a> {
         FilterOptions {
             on_changes: true,
             folder,
+            explore_options: ExploreOptions::default(),
         }
     }
-}
-
-pub fn explore_path(
-    path: &Path,
-    filter_options: FilterOptions<'_>,
-) -> Result<Vec<(PathBuf, Mxfile)>> {
-    let drawio_paths: Vec<PathBuf> = collect_files_from_filesystem(path);
 
-    let mut files: Vec<(PathBuf, Mxfile)> = vec![];
-    for drawio_path in drawio_paths {
-        files.push((drawio_path.clone(), read_file(&drawio_path)?))
+    pub fn with_explore_options(mut self, explore_options: &ExploreOptions) -> FilterOptions<'a> {
+        self.explore_options = explore_options.clone();
+        self
     }
+}
 
-    files.sort_by(|(a, _), (b, _)| a.cmp(b));
+pub fn explore_path(path: &Path, filter_options: FilterOptions<'_>) -> Result<ExploredFiles> {
+    let drawio_paths: Vec<PathBuf> = collect_files(path, &filter_options.explore_options)?;
 
-    match filter_options.on_changes {
-        true => Ok(only_keep_changed_drawio_files(files, filter_options.folder)),
-        false => Ok(files),
+    let mut explored_files = read_files(drawio_paths, filter_options.explore_options.keep_going)?;
+    if filter_options.on_changes {
+        explored_files.files =
+            only_keep_changed_drawio_files(explored_files.files, filter_options.folder);
     }
+    Ok(explored_files)
 }
 
-fn collect_files_from_filesystem(path: &Path) -> Vec<PathBuf> {
-    WalkBuilder::new(path)
+pub(crate) fn collect_files(path: &Path, explore_options: &ExploreOptions) -> Result<Vec<PathBuf>> {
+    let extensions = explore_options.extensions();
+    // Globs are applied on the explored files, so they can't bring back ignored files
+    let globs = explore_options.globs(path)?;
+    let use_gitignore = !explore_options.no_gitignore;
+    Ok(WalkBuilder::new(path)
+        .hidden(!explore_options.hidden)
+        .follow_links(explore_options.follow_symlinks)
+        .max_depth(explore_options.max_depth)
+        .git_ignore(use_gitignore)
+        .git_global(use_gitignore)
+        .git_exclude(use_gitignore)
+        .add_custom_ignore_filename(IGNORE_FILENAME)
         .build()
         .filter_map(|r| r.ok())
-        .filter(|d| {
-            d.path().is_file()
-                && match d.path().extension() {
-                    Some(ext) => ext.eq("drawio"),
-                    None => false,
-                }
-        })
+        .filter(|d| d.path().is_file() && has_extension(d.path(), &extensions))
+        .filter(|d| !globs.matched(d.path(), false).is_ignore())
         .map(|d| d.into_path())
-        .collect::<Vec<PathBuf>>()
+        .collect::<Vec<PathBuf>>())
 }
 
 fn only_keep_changed_drawio_files(
@@ -87,11 +96,7 @@ fn is_drawio_file_older_than_exported_files(
 ) -> bool {
     let export_folder_path = path.parent().unwrap().join(export_folder);
     let path_canonicalize = path.canonicalize().unwrap_or_default();
-    let path_file_stem = path_canonicalize
-        .file_stem()
-        .unwrap_or_default()
-        .to_str()
-        .unwrap_or_default();
+    let path_file_stem = drawio_file_stem(&path_canonicalize);
     let existing_exported_files = WalkBuilder::new(export_folder_path)
         .build()
         .filter_map(|r| r.ok())
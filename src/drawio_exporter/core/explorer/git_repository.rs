@@ -1,19 +1,23 @@
-use crate::core::drawio::mxfile::{read_file, Mxfile};
+use crate::core::drawio::mxfile::{read_data, Mxfile};
+use crate::core::explorer::filesystem::collect_files;
+use crate::core::explorer::{read_files, ExploreOptions, ExploredFiles};
 use anyhow::{Context, Result};
 use git2::{DiffOptions, Object, ObjectType, Repository};
 use std::path::{Path, PathBuf};
 
-pub fn explore_path(path: &Path, git_reference: &str) -> Result<Vec<(PathBuf, Mxfile)>> {
-    let drawio_paths: Vec<PathBuf> = collect_files_from_git(path, git_reference)?;
+pub fn explore_path(
+    path: &Path,
+    git_reference: &str,
+    explore_options: &ExploreOptions,
+) -> Result<ExploredFiles> {
+    // Modified files are explored only if they would be explored on the filesystem
+    let explored_paths: Vec<PathBuf> = collect_files(path, explore_options)?;
+    let drawio_paths: Vec<PathBuf> = collect_files_from_git(path, git_reference)?
+        .into_iter()
+        .filter(|path| explored_paths.contains(path))
+        .collect();
 
-    let mut files: Vec<(PathBuf, Mxfile)> = vec![];
-    for drawio_path in drawio_paths {
-        files.push((drawio_path.clone(), read_file(&drawio_path)?))
-    }
-
-    files.sort_by(|(a, _), (b, _)| a.cmp(b));
-
-    Ok(files)
+    read_files(drawio_paths, explore_options.keep_going)
 }
 
 fn collect_files_from_git(root_path: &Path, git_reference: &str) -> Result<Vec<PathBuf>> {
@@ -37,19 +41,25 @@ fn collect_files_from_git(root_path: &Path, git_reference: &str) -> Result<Vec<P
             )
         })?;
 
+    // Keep the found paths relative to the explored path, like on the filesystem
+    let canonical_root_path = root_path
+        .canonicalize()
+        .with_context(|| format!("can't resolve path {}", root_path.display()))?;
+    // Delta paths are relative to the working directory of the repository
+    let workdir = repo.workdir().unwrap_or_else(|| Path::new("."));
     let diff_files = diff_output
         .deltas()
-        .map(|delta| PathBuf::from(delta.new_file().path().unwrap()))
-        .filter(|path| match path.extension() {
-            Some(ext) => ext.eq("drawio"),
-            None => false,
-        })
+        .map(|delta| workdir.join(delta.new_file().path().unwrap()))
         .filter(|path| path.exists())
-        .filter(|path| {
-            path.canonicalize()
-                .unwrap()
-                .starts_with(root_path.canonicalize().unwrap())
-        })
+        .filter_map(
+            |path| match path.canonicalize().ok()?.strip_prefix(&canonical_root_path) {
+                Ok(relative_path) if relative_path.as_os_str().is_empty() => {
+                    Some(root_path.to_path_buf())
+                }
+                Ok(relative_path) => Some(root_path.join(relative_path)),
+                Err(_) => None,
+            },
+        )
         .collect::<Vec<PathBuf>>();
 
     Ok(diff_files)
@@ -60,3 +70,29 @@ fn reference_as_tree<'a>(repo: &'a Repository, git_reference: &'a str) -> Result
     let tree_object = object.peel(ObjectType::Tree)?;
     Ok(tree_object)
 }
+
+pub fn read_file_at_reference(path: &Path, git_reference: &str) -> Result<Option<Mxfile>> {
+    let repo = Repository::discover(path)
+        .with_context(|| format!("need to be a git repository {}", &path.display()))?;
+    let workdir = repo
+        .workdir()
+        .with_context(|| format!("need a working directory on {}", repo.path().display()))?;
+    let relative_path = path
+        .canonicalize()?
+        .strip_prefix(workdir.canonicalize()?)?
+        .to_path_buf();
+    let tree_object = reference_as_tree(&repo, git_reference).with_context(|| {
+        format!(
+            "can't found reference {} on {}",
+            git_reference,
+            repo.path().display()
+        )
+    })?;
+    let entry = match tree_object.as_tree().unwrap().get_path(&relative_path) {
+        Ok(entry) => entry,
+        // The file don't exists at this reference
+        Err(_) => return Ok(None),
+    };
+    let blob = entry.to_object(&repo)?.peel_to_blob()?;
+    Ok(Some(read_data(path, blob.content())?))
+}
@@ -1,2 +1,105 @@
+use anyhow::{Context, Result};
+use ignore::overrides::{Override, OverrideBuilder};
+use std::path::{Path, PathBuf};
+
+use crate::core::drawio::mxfile::{read_file, Mxfile};
+
 pub mod filesystem;
 pub mod git_repository;
+
+/// Extensions of the explored files, when none are configured
+pub const DEFAULT_EXTENSIONS: [&str; 3] = ["drawio", "drawio.svg", "drawio.png"];
+
+/// Name of the ignore files, using the gitignore syntax, read in the explored folders
+pub const IGNORE_FILENAME: &str = ".drawioexporterignore";
+
+#[derive(Debug, Default, Clone)]
+pub struct ExploreOptions {
+    /// Extensions of the explored files (the default ones if empty)
+    pub extensions: Vec<String>,
+    /// Only explore the files matching one of these globs (all files if empty)
+    pub include: Vec<String>,
+    /// Don't explore the files matching one of these globs
+    pub exclude: Vec<String>,
+    pub hidden: bool,
+    pub follow_symlinks: bool,
+    pub max_depth: Option<usize>,
+    pub no_gitignore: bool,
+    /// Keep going when a file can't be read, instead of stopping at the first error
+    pub keep_going: bool,
+}
+
+/// Error on a single file, which don't stop the other files to be processed
+#[derive(Debug)]
+pub struct FileError {
+    pub path: PathBuf,
+    pub error: anyhow::Error,
+}
+
+#[derive(Debug, Default)]
+pub struct ExploredFiles {
+    pub files: Vec<(PathBuf, Mxfile)>,
+    /// Files which can't be read (only when keeping going)
+    pub errors: Vec<FileError>,
+}
+
+impl ExploreOptions {
+    pub(crate) fn extensions(&self) -> Vec<String> {
+        match self.extensions.is_empty() {
+            true => DEFAULT_EXTENSIONS.iter().map(|e| e.to_string()).collect(),
+            false => self
+                .extensions
+                .iter()
+                .map(|e| e.trim_start_matches('.').to_string())
+                .collect(),
+        }
+    }
+
+    /// Include and exclude globs, relative to the explored path
+    pub(crate) fn globs(&self, path: &Path) -> Result<Override> {
+        let root = match path.is_file() {
+            true => path.parent().unwrap_or(path),
+            false => path,
+        };
+        let mut builder = OverrideBuilder::new(root);
+        for glob in &self.include {
+            builder
+                .add(glob)
+                .with_context(|| format!("invalid include glob {}", glob))?;
+        }
+        for glob in &self.exclude {
+            builder
+                .add(&format!("!{}", glob))
+                .with_context(|| format!("invalid exclude glob {}", glob))?;
+        }
+        builder.build().context("can't build globs")
+    }
+}
+
+pub(crate) fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    // Extensions like 'drawio.svg' are made of multiple parts, so we match the end of the name
+    let file_name = match path.file_name().and_then(|name| name.to_str()) {
+        Some(file_name) => file_name.to_ascii_lowercase(),
+        None => return false,
+    };
+    extensions.iter().any(|extension| {
+        let suffix = format!(".{}", extension.to_ascii_lowercase());
+        file_name.len() > suffix.len() && file_name.ends_with(&suffix)
+    })
+}
+
+pub(crate) fn read_files(paths: Vec<PathBuf>, keep_going: bool) -> Result<ExploredFiles> {
+    let mut explored_files = ExploredFiles::default();
+    for path in paths {
+        match read_file(&path) {
+            Ok(mxfile) => explored_files.files.push((path, mxfile)),
+            Err(error) if keep_going => explored_files.errors.push(FileError { path, error }),
+            Err(error) => return Err(error),
+        }
+    }
+
+    explored_files.files.sort_by(|(a, _), (b, _)| a.cmp(b));
+    explored_files.errors.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(explored_files)
+}
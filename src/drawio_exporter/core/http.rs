@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+pub trait HttpChecker {
+    /// Check that the url is reachable, or return the reason why it's not
+    fn check(&self, url: &str) -> Result<(), String>;
+}
+
+pub struct HttpClientChecker {
+    agent: ureq::Agent,
+}
+
+impl HttpClientChecker {
+    pub fn new(timeout: Duration) -> HttpClientChecker {
+        HttpClientChecker {
+            agent: ureq::AgentBuilder::new().timeout(timeout).build(),
+        }
+    }
+}
+
+impl HttpChecker for HttpClientChecker {
+    fn check(&self, url: &str) -> Result<(), String> {
+        match self.agent.head(url).call() {
+            Ok(_) => Ok(()),
+            // Some servers don't support HEAD requests, so we try again with a GET request
+            Err(ureq::Error::Status(405, _)) | Err(ureq::Error::Status(501, _)) => {
+                match self.agent.get(url).call() {
+                    Ok(_) => Ok(()),
+                    Err(err) => Err(http_error(err)),
+                }
+            }
+            Err(err) => Err(http_error(err)),
+        }
+    }
+}
+
+fn http_error(err: ureq::Error) -> String {
+    match err {
+        ureq::Error::Status(code, _) => format!("status code {}", code),
+        ureq::Error::Transport(transport) => match transport.message() {
+            Some(message) => format!("{}: {}", transport.kind(), message),
+            None => transport.kind().to_string(),
+        },
+    }
+}
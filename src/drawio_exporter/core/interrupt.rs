@@ -0,0 +1,30 @@
+// Interruption of long running commands (like Ctrl-C).
+//
+// Instead of stopping the process right away, the interruption is recorded, so the started
+// processes (draw.io, Xvfb) can be stopped before exiting. A second interruption stops it right away.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use anyhow::Result;
+
+static INTERRUPTED: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+fn interrupted_flag() -> &'static Arc<AtomicBool> {
+    INTERRUPTED.get_or_init(|| Arc::new(AtomicBool::new(false)))
+}
+
+/// Record the interruptions (SIGINT and SIGTERM), instead of stopping the process
+pub fn handle_interrupts() -> Result<()> {
+    #[cfg(unix)]
+    for signal in [signal_hook::consts::SIGINT, signal_hook::consts::SIGTERM] {
+        let flag = interrupted_flag();
+        signal_hook::flag::register_conditional_shutdown(signal, 130, Arc::clone(flag))?;
+        signal_hook::flag::register(signal, Arc::clone(flag))?;
+    }
+    Ok(())
+}
+
+pub fn is_interrupted() -> bool {
+    interrupted_flag().load(Ordering::Relaxed)
+}
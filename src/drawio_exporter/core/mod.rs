@@ -1,2 +1,6 @@
 pub mod drawio;
 pub mod explorer;
+pub mod http;
+pub mod interrupt;
+pub mod pdf_book;
+pub mod post_processing;
@@ -0,0 +1,404 @@
+// Book merging exported pdf files, with a cover, a table of contents,
+// and an outline (bookmarks) of its chapters and pages.
+//
+// Only the objects used by the pages of the exported files are imported,
+// their catalogs and page trees are replaced by the ones of the book.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Bookmark, Dictionary, Document, Object, ObjectId, Stream};
+
+/// Book made of chapters, like the drawio files
+pub struct Book {
+    pub title: String,
+    pub chapters: Vec<Chapter>,
+}
+
+/// Chapter made of pages, like the pages of a drawio file
+pub struct Chapter {
+    pub title: String,
+    pub pages: Vec<ChapterPage>,
+}
+
+pub struct ChapterPage {
+    pub title: String,
+    /// Exported pdf file of the page (all its pages are added to the book)
+    pub pdf: PathBuf,
+}
+
+// A4 pages, in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const TITLE_SIZE: f32 = 28.0;
+const HEADING_SIZE: f32 = 20.0;
+const TEXT_SIZE: f32 = 11.0;
+const LINE_HEIGHT: f32 = 18.0;
+const PAGE_INDENT: f32 = 16.0;
+// Lines of the table of contents on each of its pages, below its heading
+const TOC_LINES_PER_PAGE: usize = 38;
+
+// Attributes of a page which can be set on its ancestors in the page tree
+const INHERITABLE_ATTRIBUTES: [&str; 4] = ["Resources", "MediaBox", "CropBox", "Rotate"];
+const MAX_PAGE_TREE_DEPTH: usize = 32;
+
+/// Entry of the table of contents, and of the outline
+struct TocEntry<'a> {
+    title: &'a str,
+    is_chapter: bool,
+    page_number: usize,
+    page_id: ObjectId,
+}
+
+/// Write the book as a pdf file, the chapters and their pages are kept in order
+pub fn write_pdf_book(book: &Book, output: &Path) -> Result<()> {
+    let mut document = Document::with_version("1.5");
+    let pages_id = document.new_object_id();
+    let resources_id = document.add_object(dictionary! {
+        "Font" => dictionary! {
+            "F1" => font("Helvetica"),
+            "F2" => font("Helvetica-Bold"),
+        },
+    });
+
+    // The pages are imported first, as the table of contents needs their number
+    let mut chapters_pages: Vec<Vec<Vec<ObjectId>>> = vec![];
+    for chapter in &book.chapters {
+        let mut chapter_pages = vec![];
+        for page in &chapter.pages {
+            chapter_pages.push(import_pdf(&mut document, &page.pdf, pages_id)?);
+        }
+        chapters_pages.push(chapter_pages);
+    }
+
+    let toc_lines: usize = book
+        .chapters
+        .iter()
+        .map(|chapter| 1 + chapter.pages.len())
+        .sum();
+    let toc_pages_count = toc_lines.div_ceil(TOC_LINES_PER_PAGE).max(1);
+    let cover_id = document.new_object_id();
+    let toc_ids: Vec<ObjectId> = (0..toc_pages_count)
+        .map(|_| document.new_object_id())
+        .collect();
+
+    let mut toc_entries: Vec<TocEntry<'_>> = vec![];
+    let mut page_number = 2 + toc_pages_count;
+    for (chapter, chapter_pages) in book.chapters.iter().zip(&chapters_pages) {
+        let first_page_id = chapter_pages.iter().flatten().next();
+        if let Some(first_page_id) = first_page_id {
+            toc_entries.push(TocEntry {
+                title: &chapter.title,
+                is_chapter: true,
+                page_number,
+                page_id: *first_page_id,
+            });
+        }
+        for (page, page_ids) in chapter.pages.iter().zip(chapter_pages) {
+            toc_entries.push(TocEntry {
+                title: &page.title,
+                is_chapter: false,
+                page_number,
+                page_id: page_ids[0],
+            });
+            page_number += page_ids.len();
+        }
+    }
+
+    let pages_count: usize = chapters_pages.iter().flatten().map(Vec::len).sum();
+    let cover = [
+        centered_text("F2", TITLE_SIZE, PAGE_HEIGHT * 0.6, &book.title),
+        centered_text(
+            "F1",
+            TEXT_SIZE,
+            PAGE_HEIGHT * 0.6 - 2.0 * TITLE_SIZE,
+            &format!(
+                "{} drawio file(s), {} page(s)",
+                book.chapters.len(),
+                pages_count
+            ),
+        ),
+    ]
+    .concat();
+    add_page(
+        &mut document,
+        cover_id,
+        pages_id,
+        resources_id,
+        cover,
+        vec![],
+    )?;
+
+    // The table of contents has a page, even without entries
+    let toc_chunks: Vec<&[TocEntry<'_>]> = match toc_entries.is_empty() {
+        true => vec![&[]],
+        false => toc_entries.chunks(TOC_LINES_PER_PAGE).collect(),
+    };
+    for (toc_page, (toc_id, entries)) in toc_ids.iter().zip(toc_chunks).enumerate() {
+        let heading = match toc_page {
+            0 => "Table of contents",
+            _ => "Table of contents (continued)",
+        };
+        let mut operations = text("F2", HEADING_SIZE, MARGIN, PAGE_HEIGHT - MARGIN, heading);
+        let mut annotations: Vec<Object> = vec![];
+        let mut y = PAGE_HEIGHT - MARGIN - 2.0 * HEADING_SIZE;
+        for entry in entries {
+            let (font, x) = match entry.is_chapter {
+                true => ("F2", MARGIN),
+                false => ("F1", MARGIN + PAGE_INDENT),
+            };
+            let number = entry.page_number.to_string();
+            let number_x = PAGE_WIDTH - MARGIN - text_width(&number, TEXT_SIZE);
+            let title = truncated(entry.title, number_x - x - TEXT_SIZE, TEXT_SIZE);
+            operations.extend(text(font, TEXT_SIZE, x, y, &title));
+            operations.extend(text(font, TEXT_SIZE, number_x, y, &number));
+            annotations.push(
+                dictionary! {
+                    "Type" => "Annot",
+                    "Subtype" => "Link",
+                    "Rect" => vec![
+                        x.into(),
+                        (y - 4.0).into(),
+                        (PAGE_WIDTH - MARGIN).into(),
+                        (y + TEXT_SIZE + 2.0).into(),
+                    ],
+                    "Border" => vec![0.into(), 0.into(), 0.into()],
+                    "Dest" => vec![entry.page_id.into(), "Fit".into()],
+                }
+                .into(),
+            );
+            y -= LINE_HEIGHT;
+        }
+        add_page(
+            &mut document,
+            *toc_id,
+            pages_id,
+            resources_id,
+            operations,
+            annotations,
+        )?;
+    }
+
+    let kids: Vec<Object> = [cover_id]
+        .iter()
+        .chain(&toc_ids)
+        .chain(chapters_pages.iter().flatten().flatten())
+        .map(|page_id| (*page_id).into())
+        .collect();
+    document.objects.insert(
+        pages_id,
+        dictionary! {
+            "Type" => "Pages",
+            "Count" => kids.len() as i64,
+            "Kids" => kids,
+        }
+        .into(),
+    );
+    let catalog_id = document.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+        "PageMode" => "UseOutlines",
+    });
+    document.trailer.set("Root", catalog_id);
+    let info_id = document.add_object(dictionary! {
+        "Title" => Object::string_literal(pdf_text(&book.title)),
+    });
+    document.trailer.set("Info", info_id);
+
+    document.add_bookmark(
+        Bookmark::new("Table of contents".to_string(), [0.0; 3], 0, toc_ids[0]),
+        None,
+    );
+    let mut chapter_bookmark = None;
+    for entry in &toc_entries {
+        // Chapters are in bold, their pages are their children
+        match entry.is_chapter {
+            true => {
+                chapter_bookmark = Some(document.add_bookmark(
+                    Bookmark::new(entry.title.to_string(), [0.0; 3], 2, entry.page_id),
+                    None,
+                ))
+            }
+            false => {
+                document.add_bookmark(
+                    Bookmark::new(entry.title.to_string(), [0.0; 3], 0, entry.page_id),
+                    chapter_bookmark,
+                );
+            }
+        }
+    }
+    if let Some(outline_id) = document.build_outline() {
+        document
+            .get_dictionary_mut(catalog_id)?
+            .set("Outlines", outline_id);
+    }
+
+    document.compress();
+    document
+        .save(output)
+        .with_context(|| format!("can't write pdf file {}", output.display()))?;
+    Ok(())
+}
+
+/// Import the pages of the pdf file, and the objects they use, into the document
+fn import_pdf(document: &mut Document, path: &Path, pages_id: ObjectId) -> Result<Vec<ObjectId>> {
+    let mut source =
+        Document::load(path).with_context(|| format!("can't read pdf file {}", path.display()))?;
+    // The objects of the file are numbered after the ones of the document
+    source.renumber_objects_with(document.max_id + 1);
+    let page_ids: Vec<ObjectId> = source.page_iter().collect();
+    if page_ids.is_empty() {
+        anyhow::bail!("pdf file {} has no page", path.display());
+    }
+
+    let mut imported: BTreeSet<ObjectId> = BTreeSet::new();
+    let mut pending: Vec<ObjectId> = vec![];
+    for page_id in &page_ids {
+        let mut page = source
+            .get_dictionary(*page_id)
+            .with_context(|| format!("can't read the pages of pdf file {}", path.display()))?
+            .clone();
+        for attribute in INHERITABLE_ATTRIBUTES {
+            if !page.has(attribute.as_bytes()) {
+                if let Some(value) = inherited_attribute(&source, &page, attribute) {
+                    page.set(attribute, value);
+                }
+            }
+        }
+        page.set("Parent", pages_id);
+        let page = Object::Dictionary(page);
+        pending.extend(references(&page));
+        document.objects.insert(*page_id, page);
+        imported.insert(*page_id);
+    }
+
+    while let Some(object_id) = pending.pop() {
+        if !imported.insert(object_id) {
+            continue;
+        }
+        let Some(object) = source.objects.get(&object_id) else {
+            continue;
+        };
+        // Links to the catalog or the page tree of the file are not followed
+        if matches!(object.type_name(), Ok("Catalog") | Ok("Pages")) {
+            continue;
+        }
+        pending.extend(references(object));
+        document.objects.insert(object_id, object.clone());
+    }
+    document.max_id = document.max_id.max(source.max_id);
+
+    Ok(page_ids)
+}
+
+fn inherited_attribute(source: &Document, page: &Dictionary, attribute: &str) -> Option<Object> {
+    let mut node = page;
+    for _ in 0..MAX_PAGE_TREE_DEPTH {
+        let parent_id = node.get(b"Parent").and_then(Object::as_reference).ok()?;
+        node = source.get_dictionary(parent_id).ok()?;
+        if let Ok(value) = node.get(attribute.as_bytes()) {
+            return Some(value.clone());
+        }
+    }
+    None
+}
+
+/// Objects referenced by the object, or by the objects it contains
+fn references(object: &Object) -> Vec<ObjectId> {
+    match object {
+        Object::Reference(object_id) => vec![*object_id],
+        Object::Array(objects) => objects.iter().flat_map(references).collect(),
+        Object::Dictionary(dictionary) => dictionary_references(dictionary),
+        Object::Stream(stream) => dictionary_references(&stream.dict),
+        _ => vec![],
+    }
+}
+
+fn dictionary_references(dictionary: &Dictionary) -> Vec<ObjectId> {
+    dictionary
+        .iter()
+        .flat_map(|(_, object)| references(object))
+        .collect()
+}
+
+fn add_page(
+    document: &mut Document,
+    page_id: ObjectId,
+    pages_id: ObjectId,
+    resources_id: ObjectId,
+    operations: Vec<Operation>,
+    annotations: Vec<Object>,
+) -> Result<()> {
+    let content_id = document.add_object(Stream::new(
+        dictionary! {},
+        Content { operations }.encode()?,
+    ));
+    document.objects.insert(
+        page_id,
+        dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), PAGE_WIDTH.into(), PAGE_HEIGHT.into()],
+            "Contents" => content_id,
+            "Resources" => resources_id,
+            "Annots" => annotations,
+        }
+        .into(),
+    );
+    Ok(())
+}
+
+fn font(name: &str) -> Dictionary {
+    dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => name,
+        "Encoding" => "WinAnsiEncoding",
+    }
+}
+
+fn text(font: &str, size: f32, x: f32, y: f32, text: &str) -> Vec<Operation> {
+    vec![
+        Operation::new("BT", vec![]),
+        Operation::new("Tf", vec![font.into(), size.into()]),
+        Operation::new("Td", vec![x.into(), y.into()]),
+        Operation::new("Tj", vec![Object::string_literal(pdf_text(text))]),
+        Operation::new("ET", vec![]),
+    ]
+}
+
+fn centered_text(font: &str, size: f32, y: f32, content: &str) -> Vec<Operation> {
+    let content = truncated(content, PAGE_WIDTH - 2.0 * MARGIN, size);
+    let x = (PAGE_WIDTH - text_width(&content, size)) / 2.0;
+    text(font, size, x, y, &content)
+}
+
+/// Approximate width of the text in Helvetica (digits are exactly this wide)
+fn text_width(text: &str, size: f32) -> f32 {
+    text.chars().count() as f32 * size * 0.556
+}
+
+/// Shorten the text written at the font size to fit in the width, with an ellipsis
+fn truncated(text: &str, width: f32, size: f32) -> String {
+    let max_chars = (width / text_width("0", size)) as usize;
+    match text.chars().count() > max_chars {
+        true => {
+            let text: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+            format!("{}...", text)
+        }
+        false => text.to_string(),
+    }
+}
+
+/// Encode the text for the standard fonts, the characters they don't have are replaced
+fn pdf_text(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x20..=0x7e | 0xa0..=0xff => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
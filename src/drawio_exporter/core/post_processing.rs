@@ -0,0 +1,273 @@
+// Post-processing of the exported files, configured for each exported format.
+//
+// The steps which only change the files are here,
+// the ones exporting variants of the pages with draw.io are run by the exporter.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ImageFormat};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, Writer};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PostProcessStep {
+    /// Lossless optimization of png files
+    Optimize,
+    /// Removal of the comments and the indentation of svg files
+    Minify,
+    /// Removal of the diagram embedded by draw.io in png and svg files
+    StripMetadata,
+    /// Variant of the page exported at a scale, like `name@2x.png` for 2x
+    Scale(f32),
+    /// Lossless conversion of png files to webp, like `name.webp`
+    Webp,
+}
+
+impl PostProcessStep {
+    /// Parse a step, like `optimize` or `scale=2x`
+    pub fn parse(step: &str) -> Result<PostProcessStep> {
+        match step.split_once('=') {
+            None if step == "optimize" => Ok(PostProcessStep::Optimize),
+            None if step == "minify" => Ok(PostProcessStep::Minify),
+            None if step == "strip-metadata" => Ok(PostProcessStep::StripMetadata),
+            None if step == "webp" => Ok(PostProcessStep::Webp),
+            Some(("scale", factor)) => match factor.trim_end_matches('x').parse::<f32>() {
+                Ok(factor) if factor > 0.0 => Ok(PostProcessStep::Scale(factor)),
+                _ => Err(anyhow!(
+                    "scale '{}' must be a positive number, like 2x or 1.5x",
+                    factor
+                )),
+            },
+            _ => Err(anyhow!(
+                "unknown post-processing step '{}' (optimize, minify, strip-metadata, scale=<factor>x, or webp)",
+                step
+            )),
+        }
+    }
+
+    /// Exported formats supported by the step
+    pub fn formats(&self) -> &'static [&'static str] {
+        match self {
+            PostProcessStep::Optimize => &["png"],
+            PostProcessStep::Minify => &["svg"],
+            PostProcessStep::StripMetadata => &["png", "svg"],
+            PostProcessStep::Scale(_) => &["png", "jpg"],
+            PostProcessStep::Webp => &["png"],
+        }
+    }
+}
+
+impl fmt::Display for PostProcessStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PostProcessStep::Optimize => write!(f, "optimize"),
+            PostProcessStep::Minify => write!(f, "minify"),
+            PostProcessStep::StripMetadata => write!(f, "strip-metadata"),
+            PostProcessStep::Scale(factor) => write!(f, "scale={}x", factor),
+            PostProcessStep::Webp => write!(f, "webp"),
+        }
+    }
+}
+
+/// Post-processing steps of each exported format, run in order after the export of each page
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PostProcessing {
+    steps: BTreeMap<String, Vec<PostProcessStep>>,
+}
+
+impl PostProcessing {
+    pub fn new() -> PostProcessing {
+        PostProcessing::default()
+    }
+
+    /// Parse the steps of a format, like `png:optimize,scale=2x,webp`
+    pub fn parse(configuration: &str) -> Result<(String, Vec<PostProcessStep>)> {
+        let (format, steps) = configuration.split_once(':').ok_or_else(|| {
+            anyhow!(
+                "post-processing '{}' must be formatted as format:step[,step...]",
+                configuration
+            )
+        })?;
+        let steps = steps
+            .split(',')
+            .map(|step| {
+                let step = PostProcessStep::parse(step.trim())?;
+                match step.formats().contains(&format) {
+                    true => Ok(step),
+                    false => Err(anyhow!(
+                        "post-processing step {} doesn't support {} format (only {})",
+                        step,
+                        format,
+                        step.formats().join(", ")
+                    )),
+                }
+            })
+            .collect::<Result<Vec<PostProcessStep>>>()?;
+        Ok((format.to_string(), steps))
+    }
+
+    /// Add steps to the ones of the format
+    pub fn add(&mut self, format: String, steps: Vec<PostProcessStep>) {
+        self.steps.entry(format).or_default().extend(steps);
+    }
+
+    /// Steps of the exported format (png for adoc and md formats)
+    pub fn steps(&self, format: &str) -> &[PostProcessStep] {
+        self.steps
+            .get(format)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+/// Path of the variant of an exported file at a scale, like `name@2x.png`
+pub fn scaled_path(path: &Path, factor: f32) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path.extension().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}@{}x.{}", stem, factor, extension))
+}
+
+/// Optimize the png file, without changing its pixels nor its chunks
+pub fn optimize_png(path: &Path) -> Result<()> {
+    let content = fs::read(path).with_context(|| format!("can't read {}", path.display()))?;
+    let optimized = oxipng::optimize_from_memory(&content, &oxipng::Options::from_preset(2))
+        .map_err(|err| anyhow!("can't optimize {}: {}", path.display(), err))?;
+    if optimized.len() < content.len() {
+        fs::write(path, optimized).with_context(|| format!("can't write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Remove the comments and the indentation of the svg file
+/// (the other whitespaces are kept, as they can be part of the labels,
+/// like all the ones of the elements with a `xml:space="preserve"` attribute)
+pub fn minify_svg(path: &Path) -> Result<()> {
+    // Depth of the elements inside an element preserving its whitespaces
+    let mut preserved_depth = 0;
+    rewrite_svg(path, |event| {
+        match &event {
+            Event::Start(element) if preserved_depth > 0 || preserves_space(element) => {
+                preserved_depth += 1
+            }
+            Event::End(_) if preserved_depth > 0 => preserved_depth -= 1,
+            _ => {}
+        }
+        match event {
+            Event::Comment(_) => None,
+            Event::Text(text)
+                if preserved_depth == 0
+                    && text.iter().all(u8::is_ascii_whitespace)
+                    && text.contains(&b'\n') =>
+            {
+                None
+            }
+            event => Some(event),
+        }
+    })
+}
+
+fn preserves_space(element: &BytesStart<'_>) -> bool {
+    element.attributes().flatten().any(|attribute| {
+        attribute.key.as_ref() == b"xml:space" && attribute.value.as_ref() == b"preserve"
+    })
+}
+
+/// Remove the diagram embedded by draw.io in the file
+/// (text chunks of png files, and `content` attribute of the root element of svg files)
+pub fn strip_metadata(path: &Path, format: &str) -> Result<()> {
+    match format {
+        "png" => strip_png_text_chunks(path),
+        "svg" => {
+            let mut is_root = true;
+            rewrite_svg(path, |event| match event {
+                Event::Start(element) if is_root => {
+                    is_root = false;
+                    Some(Event::Start(without_content_attribute(&element)))
+                }
+                Event::Empty(element) if is_root => {
+                    is_root = false;
+                    Some(Event::Empty(without_content_attribute(&element)))
+                }
+                event => Some(event),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Convert the png file to a webp file next to it, and return its path
+pub fn convert_to_webp(path: &Path) -> Result<PathBuf> {
+    let content = fs::read(path).with_context(|| format!("can't read {}", path.display()))?;
+    let image = image::load_from_memory_with_format(&content, ImageFormat::Png)
+        .with_context(|| format!("can't read image {}", path.display()))?;
+    let webp_path = path.with_extension("webp");
+    let file = File::create(&webp_path)
+        .with_context(|| format!("can't create {}", webp_path.display()))?;
+    // The lossless encoder supports rgb and rgba images only
+    DynamicImage::ImageRgba8(image.to_rgba8())
+        .write_with_encoder(WebPEncoder::new_lossless(BufWriter::new(file)))
+        .with_context(|| format!("can't write {}", webp_path.display()))?;
+    Ok(webp_path)
+}
+
+fn rewrite_svg<'a>(
+    path: &Path,
+    mut rewrite: impl FnMut(Event<'a>) -> Option<Event<'a>>,
+) -> Result<()> {
+    let content = fs::read(path).with_context(|| format!("can't read {}", path.display()))?;
+    let mut reader = Reader::from_reader(content.as_slice());
+    let mut writer = Writer::new(Vec::with_capacity(content.len()));
+    loop {
+        let event = reader
+            .read_event()
+            .with_context(|| format!("can't read svg file {}", path.display()))?;
+        if event == Event::Eof {
+            break;
+        }
+        if let Some(event) = rewrite(event.into_owned()) {
+            writer.write_event(event)?;
+        }
+    }
+    fs::write(path, writer.into_inner()).with_context(|| format!("can't write {}", path.display()))
+}
+
+fn without_content_attribute(element: &BytesStart<'_>) -> BytesStart<'static> {
+    let name = String::from_utf8_lossy(element.name().as_ref()).into_owned();
+    BytesStart::new(name).with_attributes(
+        element
+            .attributes()
+            .flatten()
+            .filter(|attribute| attribute.key.as_ref() != b"content"),
+    )
+}
+
+const PNG_SIGNATURE_LENGTH: usize = 8;
+const PNG_TEXT_CHUNKS: [&[u8]; 3] = [b"tEXt", b"zTXt", b"iTXt"];
+
+fn strip_png_text_chunks(path: &Path) -> Result<()> {
+    let content = fs::read(path).with_context(|| format!("can't read {}", path.display()))?;
+    let invalid = || anyhow!("png file {} has an invalid chunk", path.display());
+    let mut stripped = content[..PNG_SIGNATURE_LENGTH.min(content.len())].to_vec();
+    let mut position = PNG_SIGNATURE_LENGTH;
+    while position < content.len() {
+        // Length, type, data, and crc of the chunk
+        let length = content
+            .get(position..position + 4)
+            .map(|length| u32::from_be_bytes([length[0], length[1], length[2], length[3]]))
+            .ok_or_else(invalid)? as usize;
+        let end = position + 12 + length;
+        let chunk = content.get(position..end).ok_or_else(invalid)?;
+        if !PNG_TEXT_CHUNKS.contains(&&chunk[4..8]) {
+            stripped.extend_from_slice(chunk);
+        }
+        position = end;
+    }
+    fs::write(path, stripped).with_context(|| format!("can't write {}", path.display()))
+}
@@ -0,0 +1,96 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use crate::core::drawio::mxfile::Mxfile;
+use crate::core::explorer::filesystem;
+use crate::core::explorer::filesystem::FilterOptions;
+use crate::core::explorer::{ExploreOptions, ExploredFiles};
+use crate::core::pdf_book::{write_pdf_book, Book, Chapter, ChapterPage};
+use crate::ops::exporter::exported_path;
+use crate::ops::{display_path, input_path, keep_file_error, report_file_errors};
+
+pub struct BookOptions<'a> {
+    /// Exported folder name, where the pdf files of the pages are
+    pub folder: &'a String,
+    pub remove_page_suffix: bool,
+    /// Path of the book pdf file
+    pub file: &'a String,
+    pub title: &'a String,
+    pub explore_options: &'a ExploreOptions,
+    pub path: &'a str,
+}
+
+pub fn book(options: BookOptions<'_>) -> Result<()> {
+    let input_path = input_path(options.path)?;
+    let ExploredFiles {
+        files: mut drawio_files,
+        errors: mut file_errors,
+    } = filesystem::explore_path(
+        &input_path,
+        FilterOptions::no_filtering().with_explore_options(options.explore_options),
+    )
+    .with_context(|| format!("can't explore path {}", &input_path.display()))?;
+    let files_count = drawio_files.len() + file_errors.len();
+
+    // Ordered by path, then by page
+    drawio_files.sort_by(|(path, _), (other_path, _)| path.cmp(other_path));
+    let mut chapters: Vec<Chapter> = vec![];
+    for (path, mxfile) in drawio_files {
+        if mxfile.diagrams.is_empty() {
+            continue;
+        }
+        println!("+ add file : {}", display_path(options.path, &path));
+        match chapter(&options, &path, &mxfile) {
+            Ok(chapter) => chapters.push(chapter),
+            Err(error) => keep_file_error(
+                path,
+                Err(error),
+                options.explore_options.keep_going,
+                &mut file_errors,
+            )?,
+        }
+    }
+    if chapters.is_empty() {
+        anyhow::bail!("no exported page to put in the book");
+    }
+
+    println!("+ write book : {}", options.file);
+    write_pdf_book(
+        &Book {
+            title: options.title.to_string(),
+            chapters,
+        },
+        Path::new(options.file),
+    )
+    .with_context(|| format!("can't write book {}", options.file))?;
+
+    report_file_errors(options.path, &file_errors, files_count)
+}
+
+fn chapter(options: &BookOptions<'_>, path: &Path, mxfile: &Mxfile) -> Result<Chapter> {
+    let with_page_suffix = !(options.remove_page_suffix && mxfile.diagrams.len() == 1);
+    let pages = mxfile
+        .diagrams
+        .iter()
+        .enumerate()
+        .map(|(position, diagram)| {
+            let pdf = exported_path(path, options.folder, diagram, with_page_suffix, "pdf");
+            if !pdf.exists() {
+                anyhow::bail!(
+                    "can't find {} for page {} : {}, export the drawio files in pdf format first",
+                    display_path(options.path, &pdf),
+                    position + 1,
+                    diagram.name
+                );
+            }
+            Ok(ChapterPage {
+                title: diagram.name.clone(),
+                pdf,
+            })
+        })
+        .collect::<Result<Vec<ChapterPage>>>()?;
+    Ok(Chapter {
+        title: display_path(options.path, path),
+        pages,
+    })
+}
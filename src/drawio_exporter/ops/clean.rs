@@ -0,0 +1,74 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::core::explorer::filesystem;
+use crate::core::explorer::filesystem::FilterOptions;
+use crate::core::explorer::{ExploreOptions, ExploredFiles};
+use crate::ops::{display_path, input_path, report_file_errors};
+
+pub struct CleanOptions<'a> {
+    pub folder: &'a String,
+    pub explore_options: &'a ExploreOptions,
+    pub path: &'a str,
+}
+
+pub fn clean(options: CleanOptions<'_>) -> Result<()> {
+    // The export folders are removed next to each drawio file, so they must stay next to them
+    let mut components = Path::new(options.folder).components();
+    if options.folder.contains('\\')
+        || !matches!(
+            (components.next(), components.next()),
+            (Some(Component::Normal(_)), None)
+        )
+    {
+        anyhow::bail!(
+            "export folder '{}' must be a folder name, without any path",
+            options.folder
+        );
+    }
+
+    let input_path = input_path(options.path)?;
+    let ExploredFiles {
+        files: drawio_files,
+        errors: file_errors,
+    } = filesystem::explore_path(
+        &input_path,
+        FilterOptions::no_filtering().with_explore_options(options.explore_options),
+    )
+    .with_context(|| format!("can't explore path {}", &input_path.display()))?;
+    let files_count = drawio_files.len() + file_errors.len();
+
+    let mut export_folders: Vec<PathBuf> = drawio_files
+        .iter()
+        .map(|(path, _)| path.parent().unwrap().join(options.folder))
+        .filter(|export_folder| export_folder.is_dir())
+        .collect();
+    export_folders.sort();
+    export_folders.dedup();
+
+    // Nothing is removed when a folder isn't only an export folder
+    for export_folder in &export_folders {
+        if let Some((path, _)) = drawio_files
+            .iter()
+            .find(|(path, _)| path.starts_with(export_folder))
+        {
+            anyhow::bail!(
+                "can't remove export folder {}, it contains the drawio file {}",
+                display_path(options.path, export_folder),
+                display_path(options.path, path)
+            );
+        }
+    }
+
+    for export_folder in export_folders {
+        println!(
+            "- remove folder : {}",
+            display_path(options.path, &export_folder)
+        );
+        fs::remove_dir_all(&export_folder)
+            .with_context(|| format!("can't remove export folder {}", export_folder.display()))?;
+    }
+
+    report_file_errors(options.path, &file_errors, files_count)
+}
@@ -0,0 +1,85 @@
+use anyhow::{Context, Result};
+
+use crate::core::drawio::mxfile::Mxfile;
+use crate::core::drawio::writer::normalized_page_xml;
+use crate::core::explorer::git_repository;
+use crate::core::explorer::{ExploreOptions, ExploredFiles, FileError};
+use crate::ops::{display_path, input_path, keep_file_error, report_file_errors};
+
+pub struct DiffOptions<'a> {
+    pub git_reference: &'a String,
+    pub explore_options: &'a ExploreOptions,
+    pub path: &'a str,
+}
+
+pub fn diff(options: DiffOptions<'_>) -> Result<()> {
+    let input_path = input_path(options.path)?;
+    let ExploredFiles {
+        files: drawio_files,
+        errors: mut file_errors,
+    } = git_repository::explore_path(&input_path, options.git_reference, options.explore_options)
+        .with_context(|| format!("can't explore path {}", &input_path.display()))?;
+    let files_count = drawio_files.len() + file_errors.len();
+
+    for (path, mxfile) in drawio_files {
+        println!("+ diff file : {}", display_path(options.path, &path));
+        let previous_mxfile = git_repository::read_file_at_reference(&path, options.git_reference)
+            .with_context(|| {
+                format!(
+                    "can't read {} at reference {}",
+                    path.display(),
+                    options.git_reference
+                )
+            });
+        let previous_mxfile = match previous_mxfile {
+            Ok(previous_mxfile) => previous_mxfile.unwrap_or_default(),
+            Err(error) if options.explore_options.keep_going => {
+                file_errors.push(FileError { path, error });
+                continue;
+            }
+            Err(error) => return Err(error),
+        };
+        let changes = diff_mxfiles(&previous_mxfile, &mxfile);
+        match changes {
+            Ok(changes) => changes.iter().for_each(|change| println!("{}", change)),
+            Err(error) => keep_file_error(
+                path,
+                Err(error),
+                options.explore_options.keep_going,
+                &mut file_errors,
+            )?,
+        }
+    }
+
+    report_file_errors(options.path, &file_errors, files_count)
+}
+
+fn diff_mxfiles(previous: &Mxfile, current: &Mxfile) -> Result<Vec<String>> {
+    let mut changes: Vec<String> = vec![];
+
+    for diagram in &current.diagrams {
+        match previous.diagrams.iter().find(|d| d.id == diagram.id) {
+            None => changes.push(format!("- page added : {}", diagram.name)),
+            Some(previous_diagram) => {
+                if previous_diagram.name != diagram.name {
+                    changes.push(format!(
+                        "- page renamed : {} -> {}",
+                        previous_diagram.name, diagram.name
+                    ));
+                }
+                // The whole page is compared, like the geometry and the style of the shapes
+                if normalized_page_xml(previous_diagram)? != normalized_page_xml(diagram)? {
+                    changes.push(format!("- page changed : {}", diagram.name));
+                }
+            }
+        }
+    }
+
+    for previous_diagram in &previous.diagrams {
+        if !current.diagrams.iter().any(|d| d.id == previous_diagram.id) {
+            changes.push(format!("- page removed : {}", previous_diagram.name));
+        }
+    }
+
+    Ok(changes)
+}
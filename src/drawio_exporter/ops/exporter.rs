@@ -1,24 +1,44 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{Context, Result};
 
-use crate::core::drawio::drawio_desktop::{DrawioDesktop, ExportArguments};
-use crate::core::drawio::mxfile::{Diagram, Mxfile};
+use crate::core::drawio::drawio_desktop::ExportArguments;
+use crate::core::drawio::export_backend::{ExportBackend, TransientExportError};
+use crate::core::drawio::exported_file::verify_exported_file;
+use crate::core::drawio::mxfile::{drawio_file_stem, Diagram, LinkKind, Mxfile};
 use crate::core::explorer::filesystem;
 use crate::core::explorer::filesystem::FilterOptions;
 use crate::core::explorer::git_repository;
-use relative_path::RelativePath;
-use std::ffi::OsStr;
+use crate::core::explorer::ExploreOptions;
+use crate::core::explorer::ExploredFiles;
+use crate::core::interrupt::is_interrupted;
+use crate::core::post_processing::{
+    convert_to_webp, minify_svg, optimize_png, scaled_path, strip_metadata, PostProcessStep,
+    PostProcessing,
+};
+use crate::ops::text::{write_search_index, SearchIndexEntry};
+use crate::ops::{
+    display_path, input_path, keep_file_error, report_file_errors, resolve_placeholders,
+};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
 
 pub struct ExporterOptions<'a> {
-    pub application: &'a String,
-    pub drawio_desktop_headless: bool,
+    /// Export the pages, with draw.io desktop or another backend
+    pub backend: &'a dyn ExportBackend,
+    /// Export again a page after a transient failure (like a timeout), up to this number of times
+    pub retries: u32,
+    /// Delay before the first retry, doubled on each retry
+    pub retry_delay: Duration,
     pub folder: &'a String,
     pub on_filesystem_changes: bool,
     pub on_git_changes_since_reference: Option<&'a String>,
     pub remove_page_suffix: bool,
+    pub explore_options: &'a ExploreOptions,
     pub path: &'a str,
     pub format: &'a String,
     pub border: &'a String,
@@ -32,126 +52,679 @@ pub struct ExporterOptions<'a> {
     pub uncompressed: bool,
     pub embed_svg_images: bool,
     pub embed_diagram: bool,
+    pub batch: bool,
+    /// Export the pages of each drawio file into a single pdf file, named after the drawio file
+    pub single_pdf: bool,
+    /// Pages exported into the single pdf file (all the pages when not set)
+    pub page_range: Option<PageRange>,
+    /// Steps run on the exported files of each format
+    pub post_processing: &'a PostProcessing,
+    pub search_index: Option<&'a String>,
+    pub text_alternative: bool,
+    pub variables: &'a BTreeMap<String, String>,
+}
+
+/// Range of pages, numbered from 1 (like `2..4`), the last page is included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRange {
+    pub from: usize,
+    pub to: usize,
+}
+
+impl PageRange {
+    pub fn parse(range: &str) -> Result<PageRange> {
+        let page_range = range
+            .split_once("..")
+            .and_then(|(from, to)| Some((from.parse().ok()?, to.parse().ok()?)))
+            .map(|(from, to)| PageRange { from, to });
+        match page_range {
+            Some(page_range) if page_range.from > 0 && page_range.from <= page_range.to => {
+                Ok(page_range)
+            }
+            _ => anyhow::bail!(
+                "page range '{}' must be formatted as from..to, with pages numbered from 1",
+                range
+            ),
+        }
+    }
+}
+
+impl fmt::Display for PageRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.from, self.to)
+    }
 }
 
 pub fn exporter(options: ExporterOptions<'_>) -> Result<()> {
-    // Fallback in case of empty path, we take the current directory
-    let input_path = match options.path {
-        "" => PathBuf::from("."),
-        path => PathBuf::from(path),
-    };
-    if !input_path.exists() {
-        return Err(anyhow!(format!(
-            "path '{}' must exist (as directory or file)",
-            options.path
-        )));
+    if options.single_pdf && options.format != "pdf" {
+        anyhow::bail!(
+            "single pdf export needs pdf format, not {} format",
+            options.format
+        );
+    }
+    if options.page_range.is_some() && !options.single_pdf {
+        anyhow::bail!("page range is only used for single pdf export");
     }
 
-    let drawio_files = match options.on_git_changes_since_reference {
+    let input_path = input_path(options.path)?;
+
+    let ExploredFiles {
+        files: drawio_files,
+        errors: mut file_errors,
+    } = match options.on_git_changes_since_reference {
         None => {
             let filter_options = match options.on_filesystem_changes {
                 true => FilterOptions::filter_on(options.folder),
                 false => FilterOptions::no_filtering(),
             };
-            filesystem::explore_path(&input_path, filter_options)
+            filesystem::explore_path(
+                &input_path,
+                filter_options.with_explore_options(options.explore_options),
+            )
+        }
+        Some(git_reference) => {
+            git_repository::explore_path(&input_path, git_reference, options.explore_options)
         }
-        Some(git_reference) => git_repository::explore_path(&input_path, git_reference),
     }
     .with_context(|| format!("can't explore path {}", &input_path.display()))?;
+    let files_count = drawio_files.len() + file_errors.len();
 
-    let drawio_desktop = DrawioDesktop::new(options.application, options.drawio_desktop_headless)?;
+    // Only the options matter to check the backend, not the files,
+    // but it isn't checked without any file to export (like discovering draw.io)
+    if !drawio_files.is_empty() {
+        let default_scale = String::from("1");
+        let mut arguments = export_arguments(&options, "", "", false, None);
+        // Set like the exports would set them, so the backend knows all of them
+        let page_range = options
+            .page_range
+            .filter(|_| options.single_pdf)
+            .map(|page_range| page_range.to_string());
+        arguments.page_range = page_range.as_ref();
+        let has_scale_step = options
+            .post_processing
+            .steps(image_format(options.format))
+            .iter()
+            .any(|step| matches!(step, PostProcessStep::Scale(_)));
+        if has_scale_step {
+            arguments.scale = arguments.scale.or(Some(&default_scale));
+        }
+        options.backend.check(&arguments)?;
+    }
 
     prepare_export_folders(options.folder, &drawio_files)
         .with_context(|| format!("can't prepare export folders named {}", options.folder))?;
 
-    let drawio_path_base = RelativePath::new(options.path);
-    for (path, mxfile) in drawio_files {
-        let drawio_file_path = drawio_path_base.relative(RelativePath::new(path.to_str().unwrap()));
-        println!("+ export file : {}", drawio_file_path);
-        let with_page_suffix = !(options.remove_page_suffix && mxfile.diagrams.len() == 1);
-        for (position, diagram) in mxfile.diagrams.iter().enumerate() {
-            let position_to_display = position + 1;
-            println!("- export page {} : {}", position_to_display, diagram.name);
-
-            let file_stem = path.file_stem().unwrap();
-            let file_stem_suffix = match with_page_suffix {
-                true => {
-                    let page_suffix = diagram.name.replace(' ', "-");
-                    format!("-{}", page_suffix)
-                }
-                false => "".to_string(),
-            };
-            let real_format = match options.format.as_str() {
-                "adoc" => "png",
-                "md" => "png",
-                _ => options.format.as_str(),
-            };
-            let output_filename = format!(
-                "{}{}.{}",
-                file_stem.to_str().unwrap(),
-                file_stem_suffix,
-                real_format
+    let mut search_index_entries: Vec<SearchIndexEntry> = vec![];
+    for (path, mut mxfile) in drawio_files {
+        println!("+ export file : {}", display_path(options.path, &path));
+        let exported = resolve_placeholders(&path, &mut mxfile, options.variables)
+            .and_then(|_| export_file(&options, &path, &mxfile, &mut search_index_entries));
+        // Even when keeping going, an interruption stops the export
+        if is_interrupted() {
+            return Err(match exported {
+                Err(error) => error.context("export interrupted"),
+                Ok(_) => anyhow::anyhow!("export interrupted"),
+            });
+        }
+        keep_file_error(
+            path,
+            exported,
+            options.explore_options.keep_going,
+            &mut file_errors,
+        )?;
+    }
+
+    if let Some(search_index) = options.search_index {
+        println!("+ write search index : {}", search_index);
+        write_search_index(Path::new(search_index), &search_index_entries)?;
+    }
+
+    report_file_errors(options.path, &file_errors, files_count)
+}
+
+fn export_file(
+    options: &ExporterOptions<'_>,
+    path: &Path,
+    mxfile: &Mxfile,
+    search_index_entries: &mut Vec<SearchIndexEntry>,
+) -> Result<()> {
+    if options.single_pdf {
+        return export_file_as_single_pdf(options, path, mxfile, search_index_entries);
+    }
+
+    let with_page_suffix = !(options.remove_page_suffix && mxfile.diagrams.len() == 1);
+    let real_format = image_format(options.format);
+    if options.batch
+        && options.backend.supports_batch()
+        && BATCH_FORMATS.contains(&real_format)
+        && mxfile.diagrams.len() > 1
+    {
+        let batch_folder = path
+            .parent()
+            .unwrap()
+            .join(options.folder)
+            .join(format!(".batch-{}", drawio_file_stem(path)));
+        let exported = export_file_in_batch(
+            options,
+            path,
+            mxfile,
+            &batch_folder,
+            with_page_suffix,
+            search_index_entries,
+        );
+        if batch_folder.exists() {
+            fs::remove_dir_all(&batch_folder)
+                .with_context(|| format!("can't remove {}", batch_folder.display()))?;
+        }
+        return exported;
+    }
+
+    for (position, diagram) in mxfile.diagrams.iter().enumerate() {
+        let position_to_display = position + 1;
+        println!("- export page {} : {}", position_to_display, diagram.name);
+
+        let output_path = exported_path(
+            path,
+            options.folder,
+            diagram,
+            with_page_suffix,
+            options.format,
+        );
+
+        println!("\\ generate {} file", real_format);
+
+        export_with_retries(
+            options,
+            &export_arguments(
+                options,
+                path.to_str().unwrap(),
+                output_path.to_str().unwrap(),
+                false,
+                Some(&position.to_string()),
+            ),
+            || verify_exported_file(&output_path, real_format),
+        )
+        .with_context(|| {
+            format!(
+                "can't export page {} : {}",
+                position_to_display, diagram.name
+            )
+        })?;
+        post_process(options, path, position, diagram, &output_path, &[])?;
+
+        complete_page_export(
+            options,
+            path,
+            position,
+            diagram,
+            with_page_suffix,
+            &output_path,
+            search_index_entries,
+        )?;
+    }
+    Ok(())
+}
+
+/// Export the pages of the file, or of the page range, into a single pdf file named after the file
+fn export_file_as_single_pdf(
+    options: &ExporterOptions<'_>,
+    path: &Path,
+    mxfile: &Mxfile,
+    search_index_entries: &mut Vec<SearchIndexEntry>,
+) -> Result<()> {
+    let pages_count = mxfile.diagrams.len();
+    // The range is limited to the pages of the file
+    let page_range = match options.page_range {
+        Some(page_range) if page_range.from > pages_count => {
+            println!(
+                "warn: no page exported, due to page range {} of a file with {} page(s)",
+                page_range, pages_count
             );
-            let output_path = path
-                .parent()
-                .unwrap()
-                .join(options.folder)
-                .join(&output_filename);
-
-            println!("\\ generate {} file", real_format);
-
-            drawio_desktop.execute(ExportArguments {
-                recursive: false,
-                output: output_path.to_str(),
-                input: path.to_str().unwrap(),
-                format: real_format,
-                border: options.border,
-                scale: options.scale,
-                width: options.width,
-                height: options.height,
-                crop: options.crop,
-                embed_diagram: options.embed_diagram,
-                transparent: options.transparent,
-                quality: options.quality,
-                uncompressed: options.uncompressed,
-                all_pages: false,
-                page_index: Some(&position.to_string()),
-                page_range: None,
-                embed_svg_images: options.embed_svg_images,
-                enable_plugins: options.enable_plugins,
-            })?;
-
-            if options.format.eq("adoc") || options.format.eq("md") {
-                generate_formatted_text_file(
-                    &options,
-                    &path,
-                    diagram,
-                    file_stem,
-                    file_stem_suffix,
-                    output_filename,
-                )?;
+            return Ok(());
+        }
+        Some(page_range) => Some(PageRange {
+            from: page_range.from,
+            to: page_range.to.min(pages_count),
+        }),
+        None if pages_count == 0 => return Ok(()),
+        None => None,
+    };
+    let (from, to) = page_range.map_or((1, pages_count), |page_range| {
+        (page_range.from, page_range.to)
+    });
+    println!("- export pages {} to {}", from, to);
+
+    let output_path = path
+        .parent()
+        .unwrap()
+        .join(options.folder)
+        .join(format!("{}.pdf", drawio_file_stem(path)));
+    println!("\\ generate pdf file");
+
+    let page_range_argument = page_range.map(|page_range| page_range.to_string());
+    let mut arguments = export_arguments(
+        options,
+        path.to_str().unwrap(),
+        output_path.to_str().unwrap(),
+        page_range.is_none(),
+        None,
+    );
+    arguments.page_range = page_range_argument.as_ref();
+    export_with_retries(options, &arguments, || {
+        verify_exported_file(&output_path, "pdf")
+    })
+    .with_context(|| format!("can't export pages {} to {}", from, to))?;
+
+    for position in (from - 1)..to {
+        complete_page_export(
+            options,
+            path,
+            position,
+            &mxfile.diagrams[position],
+            true,
+            &output_path,
+            search_index_entries,
+        )?;
+    }
+    Ok(())
+}
+
+// Formats that draw.io can export as one file per page, with a single execution
+const BATCH_FORMATS: [&str; 3] = ["png", "jpg", "svg"];
+
+/// Export all the pages with a single draw.io execution, then move the exported files
+/// to their expected names
+fn export_file_in_batch(
+    options: &ExporterOptions<'_>,
+    path: &Path,
+    mxfile: &Mxfile,
+    batch_folder: &Path,
+    with_page_suffix: bool,
+    search_index_entries: &mut Vec<SearchIndexEntry>,
+) -> Result<()> {
+    let real_format = image_format(options.format);
+    println!("\\ generate {} files of all pages", real_format);
+    let batch_files = export_all_pages(options, path, mxfile, batch_folder, options.scale)
+        .context("can't export all pages")?;
+
+    // The variants of the pages at a scale are also exported with a single execution
+    let mut scaled_batch_files: Vec<(f32, Vec<PathBuf>)> = vec![];
+    for step in options.post_processing.steps(real_format) {
+        if let PostProcessStep::Scale(factor) = step {
+            println!(
+                "\\ generate {} files of all pages at {}x scale",
+                real_format, factor
+            );
+            let scaled_batch_folder = batch_folder.join(format!("scale-{}", factor));
+            let scale = scaled(options, *factor)?;
+            let files = export_all_pages(options, path, mxfile, &scaled_batch_folder, Some(&scale))
+                .with_context(|| format!("can't export all pages at {}x scale", factor))?;
+            scaled_batch_files.push((*factor, files));
+        }
+    }
+
+    for (position, (diagram, batch_file)) in mxfile.diagrams.iter().zip(batch_files).enumerate() {
+        let position_to_display = position + 1;
+        println!("- export page {} : {}", position_to_display, diagram.name);
+
+        let output_path = exported_path(
+            path,
+            options.folder,
+            diagram,
+            with_page_suffix,
+            options.format,
+        );
+        fs::rename(&batch_file, &output_path).with_context(|| {
+            format!(
+                "can't move {} to {}",
+                batch_file.display(),
+                output_path.display()
+            )
+        })?;
+        let scaled_files: Vec<(f32, &Path)> = scaled_batch_files
+            .iter()
+            .map(|(factor, files)| (*factor, files[position].as_path()))
+            .collect();
+        post_process(
+            options,
+            path,
+            position,
+            diagram,
+            &output_path,
+            &scaled_files,
+        )?;
+
+        complete_page_export(
+            options,
+            path,
+            position,
+            diagram,
+            with_page_suffix,
+            &output_path,
+            search_index_entries,
+        )?;
+    }
+    Ok(())
+}
+
+/// Export all the pages into the batch folder with a single draw.io execution,
+/// and return the exported files in the order of the pages
+fn export_all_pages(
+    options: &ExporterOptions<'_>,
+    path: &Path,
+    mxfile: &Mxfile,
+    batch_folder: &Path,
+    scale: Option<&String>,
+) -> Result<Vec<PathBuf>> {
+    let real_format = image_format(options.format);
+    fs::create_dir_all(batch_folder)
+        .with_context(|| format!("can't create {}", batch_folder.display()))?;
+
+    let mut arguments = export_arguments(
+        options,
+        path.to_str().unwrap(),
+        batch_folder.to_str().unwrap(),
+        true,
+        None,
+    );
+    arguments.scale = scale;
+    export_with_retries(options, &arguments, || {
+        let batch_files = batch_exported_files(batch_folder, real_format)?;
+        if batch_files.len() != mxfile.diagrams.len() {
+            return Err(TransientExportError::new(format!(
+                "draw.io exported {} file(s) for {} page(s)",
+                batch_files.len(),
+                mxfile.diagrams.len()
+            ))
+            .into());
+        }
+        for batch_file in &batch_files {
+            verify_exported_file(batch_file, real_format)?;
+        }
+        Ok(batch_files)
+    })
+}
+
+/// Run the post-processing steps of the format on the exported file,
+/// each step applies to the files of the previous ones (like the variants of a scale step).
+/// The variants already exported in batch are moved, the other ones are exported for the page.
+fn post_process(
+    options: &ExporterOptions<'_>,
+    path: &Path,
+    position: usize,
+    diagram: &Diagram,
+    output_path: &Path,
+    scaled_files: &[(f32, &Path)],
+) -> Result<()> {
+    let format = image_format(options.format);
+    let mut files = vec![output_path.to_path_buf()];
+    for step in options.post_processing.steps(format) {
+        match step {
+            PostProcessStep::Scale(factor) => {
+                let variant_path = scaled_path(output_path, *factor);
+                match scaled_files
+                    .iter()
+                    .find(|(scaled_factor, _)| scaled_factor == factor)
+                {
+                    Some((_, scaled_file)) => {
+                        fs::rename(scaled_file, &variant_path).with_context(|| {
+                            format!(
+                                "can't move {} to {}",
+                                scaled_file.display(),
+                                variant_path.display()
+                            )
+                        })?
+                    }
+                    None => {
+                        println!("\\ generate {} file at {}x scale", format, factor);
+                        export_scaled_variant(options, path, position, *factor, &variant_path)
+                            .with_context(|| {
+                                format!(
+                                    "can't export page {} : {} at {}x scale",
+                                    position + 1,
+                                    diagram.name,
+                                    factor
+                                )
+                            })?
+                    }
+                }
+                files.push(variant_path);
+            }
+            PostProcessStep::Webp => {
+                println!("\\ convert {} file to webp", format);
+                let webp_files = files
+                    .iter()
+                    .map(|file| convert_to_webp(file))
+                    .collect::<Result<Vec<PathBuf>>>()?;
+                files.extend(webp_files);
+            }
+            step => {
+                println!("\\ {} {} file", step, format);
+                // The converted files are not in the exported format anymore
+                for file in files.iter().filter(|file| {
+                    file.extension().and_then(|extension| extension.to_str()) == Some(format)
+                }) {
+                    match step {
+                        PostProcessStep::Optimize => optimize_png(file)?,
+                        PostProcessStep::Minify => minify_svg(file)?,
+                        _ => strip_metadata(file, format)?,
+                    }
+                }
             }
         }
     }
+    Ok(())
+}
 
+/// Export the page again at a scale, relative to the scale of the export
+fn export_scaled_variant(
+    options: &ExporterOptions<'_>,
+    path: &Path,
+    position: usize,
+    factor: f32,
+    variant_path: &Path,
+) -> Result<()> {
+    let scale = scaled(options, factor)?;
+    let page_index = position.to_string();
+    let mut arguments = export_arguments(
+        options,
+        path.to_str().unwrap(),
+        variant_path.to_str().unwrap(),
+        false,
+        Some(&page_index),
+    );
+    arguments.scale = Some(&scale);
+    export_with_retries(options, &arguments, || {
+        verify_exported_file(variant_path, image_format(options.format))
+    })
+}
+
+/// Scale of the export multiplied by the factor
+fn scaled(options: &ExporterOptions<'_>, factor: f32) -> Result<String> {
+    let scale = match options.scale {
+        Some(scale) => scale
+            .parse::<f32>()
+            .map_err(|_| anyhow::anyhow!("scale '{}' must be a number", scale))?,
+        None => 1.0,
+    };
+    Ok((scale * factor).to_string())
+}
+
+/// Files exported by draw.io for all pages, in the order of the pages
+/// (draw.io numbers them with a suffix, like `diagram-1.png`)
+fn batch_exported_files(batch_folder: &Path, format: &str) -> Result<Vec<PathBuf>> {
+    let mut batch_files: Vec<(usize, PathBuf)> = vec![];
+    for entry in fs::read_dir(batch_folder)
+        .with_context(|| format!("can't read {}", batch_folder.display()))?
+    {
+        let batch_file = entry?.path();
+        if batch_file
+            .extension()
+            .and_then(|extension| extension.to_str())
+            != Some(format)
+        {
+            continue;
+        }
+        let page_number = batch_file
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.rsplit('-').next())
+            .and_then(|number| number.parse::<usize>().ok())
+            .unwrap_or_default();
+        batch_files.push((page_number, batch_file));
+    }
+    batch_files.sort();
+    Ok(batch_files
+        .into_iter()
+        .map(|(_, batch_file)| batch_file)
+        .collect())
+}
+
+/// Export with the backend and verify the exported files,
+/// retrying after transient failures with an increasing delay
+fn export_with_retries<T>(
+    options: &ExporterOptions<'_>,
+    arguments: &ExportArguments<'_>,
+    verify: impl Fn() -> Result<T>,
+) -> Result<T> {
+    let mut retry = 0;
+    loop {
+        match options.backend.export(arguments).and_then(|_| verify()) {
+            Err(error) if retry < options.retries && TransientExportError::is_transient(&error) => {
+                let delay = retry_delay(options.retry_delay, retry);
+                retry += 1;
+                println!(
+                    "warn: export failed, retry {} of {} in {}s: {}",
+                    retry,
+                    options.retries,
+                    delay.as_secs_f32(),
+                    error.to_string().lines().next().unwrap_or_default()
+                );
+                thread::sleep(delay);
+            }
+            exported => return exported,
+        }
+    }
+}
+
+/// Longest delay between two retries, once the doubled delay grows past it
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Delay before a retry, doubled on each retry up to one minute (or up to the first delay when it's longer)
+fn retry_delay(first_delay: Duration, retry: u32) -> Duration {
+    first_delay
+        .saturating_mul(2u32.saturating_pow(retry))
+        .min(first_delay.max(MAX_RETRY_DELAY))
+}
+
+fn export_arguments<'a>(
+    options: &'a ExporterOptions<'_>,
+    input: &'a str,
+    output: &'a str,
+    all_pages: bool,
+    page_index: Option<&'a String>,
+) -> ExportArguments<'a> {
+    ExportArguments {
+        recursive: false,
+        output: Some(output),
+        input,
+        format: image_format(options.format),
+        border: options.border,
+        scale: options.scale,
+        width: options.width,
+        height: options.height,
+        crop: options.crop,
+        embed_diagram: options.embed_diagram,
+        transparent: options.transparent,
+        quality: options.quality,
+        uncompressed: options.uncompressed,
+        all_pages,
+        page_index,
+        page_range: None,
+        embed_svg_images: options.embed_svg_images,
+        enable_plugins: options.enable_plugins,
+    }
+}
+
+/// Index the exported page, and write its adoc or md file
+fn complete_page_export(
+    options: &ExporterOptions<'_>,
+    path: &Path,
+    position: usize,
+    diagram: &Diagram,
+    with_page_suffix: bool,
+    output_path: &Path,
+    search_index_entries: &mut Vec<SearchIndexEntry>,
+) -> Result<()> {
+    if options.search_index.is_some() {
+        let mut entry = SearchIndexEntry::new(display_path(options.path, path), position, diagram)?;
+        entry.export = Some(display_path(options.path, output_path));
+        search_index_entries.push(entry);
+    }
+
+    if options.format.eq("adoc") || options.format.eq("md") {
+        let output_filename = output_path
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        generate_formatted_text_file(
+            options,
+            path,
+            diagram,
+            drawio_file_stem(path),
+            page_suffix(diagram, with_page_suffix),
+            output_filename,
+        )?;
+    }
     Ok(())
 }
 
+pub(crate) fn page_suffix(diagram: &Diagram, with_page_suffix: bool) -> String {
+    match with_page_suffix {
+        true => {
+            let page_suffix = diagram.name.replace(' ', "-");
+            format!("-{}", page_suffix)
+        }
+        false => "".to_string(),
+    }
+}
+
+pub(crate) fn image_format(format: &str) -> &str {
+    match format {
+        "adoc" => "png",
+        "md" => "png",
+        _ => format,
+    }
+}
+
+pub(crate) fn exported_path(
+    path: &Path,
+    folder: &str,
+    diagram: &Diagram,
+    with_page_suffix: bool,
+    format: &str,
+) -> PathBuf {
+    let output_filename = format!(
+        "{}{}.{}",
+        drawio_file_stem(path),
+        page_suffix(diagram, with_page_suffix),
+        image_format(format)
+    );
+    path.parent().unwrap().join(folder).join(output_filename)
+}
+
 fn generate_formatted_text_file(
     options: &ExporterOptions<'_>,
     path: &Path,
     diagram: &Diagram,
-    file_stem: &OsStr,
+    file_stem: &str,
     file_stem_suffix: String,
     output_filename: String,
 ) -> Result<()> {
     println!("\\ generate {} file", options.format);
-    let formatted_text_filename = format!(
-        "{}{}.{}",
-        file_stem.to_str().unwrap(),
-        file_stem_suffix,
-        options.format
-    );
+    let formatted_text_filename = format!("{}{}.{}", file_stem, file_stem_suffix, options.format);
     let formatted_text_path = path
         .parent()
         .unwrap()
@@ -164,65 +737,108 @@ fn generate_formatted_text_file(
             file,
             "= {} {}
 
-image::{}[{}]
-
 ",
-            file_stem.to_str().unwrap(),
-            diagram.name,
-            output_filename,
-            diagram.name
+            file_stem, diagram.name,
         )?;
+        match options.text_alternative {
+            true => write!(
+                file,
+                ".{}
+image::{}[\"{}\"]
+
+",
+                diagram.name,
+                output_filename,
+                text_alternative(diagram)?.replace('"', "\\\"")
+            )?,
+            false => write!(
+                file,
+                "image::{}[{}]
+
+",
+                output_filename, diagram.name
+            )?,
+        }
     } else if options.format.eq("md") {
         write!(
             file,
             "# {} {}
 
-![{}][{}]
-
 ",
-            file_stem.to_str().unwrap(),
-            diagram.name,
-            diagram.name,
-            output_filename,
+            file_stem, diagram.name,
         )?;
+        match options.text_alternative {
+            true => write!(
+                file,
+                "![{}][{}]
+
+_{}_
+
+",
+                text_alternative(diagram)?
+                    .replace('[', "\\[")
+                    .replace(']', "\\]"),
+                output_filename,
+                diagram.name,
+            )?,
+            false => write!(
+                file,
+                "![{}][{}]
+
+",
+                diagram.name, output_filename,
+            )?,
+        }
     }
 
     println!("\\ include links in {} file", options.format);
-    for (link, label) in diagram.get_links() {
+    for link in diagram.get_links()? {
+        let (url, label) = (link.url.as_str(), link.label.as_str());
         if label.is_empty() {
             println!(
                 "warn: link not included, due to missing label: link '[missing]' to {}",
-                link
+                url
             );
             continue;
         }
-        if link.is_empty() {
+        if url.is_empty() {
             println!(
                 "warn: link not included, due to missing url: link '{}' to [missing]",
                 label
             );
             continue;
         }
-        if link.starts_with("data:page/id") {
+        if link.kind != LinkKind::External {
             println!(
-                "warn: link not included, page link isn't supported, link '{}' to {}",
-                label, link
+                "warn: link not included, {} link isn't supported, link '{}' to {}",
+                link.kind.name(),
+                label,
+                url
             );
             continue;
         }
-        println!("link '{}' to {}", label, link);
+        println!("link '{}' to {}", label, url);
 
         if options.format.eq("adoc") {
             // Since asciidoc consider '--' string as 'Em dash' string,
             // we need to protect it in order to be usable.
-            writeln!(file, "* {}[{}]", link.replace("--", "\\--"), label)?;
+            writeln!(file, "* {}[{}]", url.replace("--", "\\--"), label)?;
         } else if options.format.eq("md") {
-            writeln!(file, "* [{}]({})", label, link)?;
+            writeln!(file, "* [{}]({})", label, url)?;
         }
     }
     Ok(())
 }
 
+/// Describe the diagram using the text content of its page
+fn text_alternative(diagram: &Diagram) -> Result<String> {
+    let text_content = diagram.get_text_content()?;
+    Ok(match text_content.labels.is_empty() {
+        true => diagram.name.clone(),
+        false => text_content.labels.join(", "),
+    })
+}
+
 fn prepare_export_folders(folder: &str, drawio_files: &[(PathBuf, Mxfile)]) -> Result<()> {
     let parent_paths: Vec<PathBuf> = drawio_files
         .iter()
@@ -239,3 +855,34 @@ fn prepare_export_folders(folder: &str, drawio_files: &[(PathBuf, Mxfile)]) -> R
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_is_doubled_on_each_retry() {
+        let first_delay = Duration::from_secs(1);
+
+        assert_eq!(retry_delay(first_delay, 0), Duration::from_secs(1));
+        assert_eq!(retry_delay(first_delay, 3), Duration::from_secs(8));
+        assert_eq!(retry_delay(first_delay, 6), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn retry_delay_with_large_retry_count() {
+        assert_eq!(
+            retry_delay(Duration::from_secs(1), 40),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            retry_delay(Duration::from_secs(1), u32::MAX),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            retry_delay(Duration::from_secs(120), 40),
+            Duration::from_secs(120)
+        );
+        assert_eq!(retry_delay(Duration::ZERO, u32::MAX), Duration::ZERO);
+    }
+}
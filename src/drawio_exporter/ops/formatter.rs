@@ -0,0 +1,61 @@
+use anyhow::{Context, Result};
+
+use crate::core::drawio::mxfile::is_embedded;
+use crate::core::drawio::writer::WriteOptions;
+use crate::core::explorer::filesystem;
+use crate::core::explorer::filesystem::FilterOptions;
+use crate::core::explorer::{ExploreOptions, ExploredFiles};
+use crate::ops::normalize::rewrite_file;
+use crate::ops::{display_path, input_path, keep_file_error, report_file_errors};
+
+pub struct FormatterOptions<'a> {
+    pub check: bool,
+    pub explore_options: &'a ExploreOptions,
+    pub path: &'a str,
+}
+
+pub fn formatter(options: FormatterOptions<'_>) -> Result<()> {
+    let input_path = input_path(options.path)?;
+    let ExploredFiles {
+        files: drawio_files,
+        errors: mut file_errors,
+    } = filesystem::explore_path(
+        &input_path,
+        FilterOptions::no_filtering().with_explore_options(options.explore_options),
+    )
+    .with_context(|| format!("can't explore path {}", &input_path.display()))?;
+    let files_count = drawio_files.len() + file_errors.len();
+
+    let write_options = WriteOptions {
+        compressed: false,
+        formatted: true,
+    };
+    let mut unformatted_files_count = 0;
+    for (path, mxfile) in drawio_files {
+        let drawio_file_path = display_path(options.path, &path);
+        if is_embedded(&path) {
+            println!("- skip file : {} (embedded diagram)", drawio_file_path);
+            continue;
+        }
+        match rewrite_file(&path, &mxfile, &write_options, !options.check) {
+            Ok(true) if options.check => {
+                println!("- not formatted file : {}", drawio_file_path);
+                unformatted_files_count += 1;
+            }
+            Ok(true) => println!("+ format file : {}", drawio_file_path),
+            Ok(false) => {}
+            Err(error) => keep_file_error(
+                path,
+                Err(error),
+                options.explore_options.keep_going,
+                &mut file_errors,
+            )?,
+        }
+    }
+
+    let reported = report_file_errors(options.path, &file_errors, files_count);
+    if unformatted_files_count > 0 {
+        anyhow::bail!("found {} not formatted file(s)", unformatted_files_count);
+    }
+    reported
+}
@@ -0,0 +1,223 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::core::drawio::mxfile::{Element, Link, LinkKind, Mxfile};
+use crate::core::explorer::filesystem;
+use crate::core::explorer::filesystem::FilterOptions;
+use crate::core::explorer::{ExploreOptions, ExploredFiles};
+use crate::core::http::HttpChecker;
+use crate::ops::{
+    display_path, input_path, keep_file_error, report_file_errors, resolve_placeholders,
+};
+
+pub struct LinksOptions<'a> {
+    pub variables: &'a BTreeMap<String, String>,
+    pub explore_options: &'a ExploreOptions,
+    pub path: &'a str,
+}
+
+pub fn links(options: LinksOptions<'_>) -> Result<()> {
+    let input_path = input_path(options.path)?;
+    let ExploredFiles {
+        files: drawio_files,
+        errors: mut file_errors,
+    } = filesystem::explore_path(
+        &input_path,
+        FilterOptions::no_filtering().with_explore_options(options.explore_options),
+    )
+    .with_context(|| format!("can't explore path {}", &input_path.display()))?;
+    let files_count = drawio_files.len() + file_errors.len();
+
+    for (path, mut mxfile) in drawio_files {
+        println!("+ file : {}", display_path(options.path, &path));
+        let listed = list_links(&path, &mut mxfile, options.variables);
+        keep_file_error(
+            path,
+            listed,
+            options.explore_options.keep_going,
+            &mut file_errors,
+        )?;
+    }
+
+    report_file_errors(options.path, &file_errors, files_count)
+}
+
+fn list_links(
+    path: &Path,
+    mxfile: &mut Mxfile,
+    variables: &BTreeMap<String, String>,
+) -> Result<()> {
+    resolve_placeholders(path, mxfile, variables)?;
+    for (position, diagram) in mxfile.diagrams.iter().enumerate() {
+        println!("- page {} : {}", position + 1, diagram.name);
+        for link in diagram.get_links()? {
+            println!("{} link '{}' to {}", link.kind.name(), link.label, link.url);
+        }
+    }
+    Ok(())
+}
+
+pub struct LinksCheckOptions<'a> {
+    pub http_checker: Option<&'a dyn HttpChecker>,
+    pub variables: &'a BTreeMap<String, String>,
+    pub explore_options: &'a ExploreOptions,
+    pub path: &'a str,
+}
+
+pub fn links_check(options: LinksCheckOptions<'_>) -> Result<()> {
+    let input_path = input_path(options.path)?;
+    let ExploredFiles {
+        files: drawio_files,
+        errors: mut file_errors,
+    } = filesystem::explore_path(
+        &input_path,
+        FilterOptions::no_filtering().with_explore_options(options.explore_options),
+    )
+    .with_context(|| format!("can't explore path {}", &input_path.display()))?;
+    let files_count = drawio_files.len() + file_errors.len();
+    // Rooted links, like /docs/guide.md, are relative to the explored folder
+    let root = match input_path.is_file() {
+        true => input_path.parent().unwrap().to_path_buf(),
+        false => input_path.clone(),
+    };
+
+    let mut dead_links_count = 0;
+    for (path, mut mxfile) in drawio_files {
+        let checked = check_links(&path, &root, &mut mxfile, &options, &mut dead_links_count);
+        keep_file_error(
+            path,
+            checked,
+            options.explore_options.keep_going,
+            &mut file_errors,
+        )?;
+    }
+
+    let reported = report_file_errors(options.path, &file_errors, files_count);
+    if dead_links_count > 0 {
+        anyhow::bail!("found {} dead link(s)", dead_links_count);
+    }
+    reported
+}
+
+fn check_links(
+    path: &Path,
+    root: &Path,
+    mxfile: &mut Mxfile,
+    options: &LinksCheckOptions<'_>,
+    dead_links_count: &mut usize,
+) -> Result<()> {
+    let drawio_file_path = display_path(options.path, path);
+    resolve_placeholders(path, mxfile, options.variables)?;
+    for (position, diagram) in mxfile.diagrams.iter().enumerate() {
+        for link in diagram.get_links()? {
+            if let Err(reason) = check_link(&link, path, root, mxfile, options) {
+                println!(
+                    "{}: page {} '{}': dead link '{}' to {} ({})",
+                    drawio_file_path,
+                    position + 1,
+                    diagram.name,
+                    link.label,
+                    link.url,
+                    reason
+                );
+                *dead_links_count += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_link(
+    link: &Link,
+    path: &Path,
+    root: &Path,
+    mxfile: &Mxfile,
+    options: &LinksCheckOptions<'_>,
+) -> std::result::Result<(), String> {
+    if link.url.is_empty() {
+        // Missing urls are reported by the lint command
+        return Ok(());
+    }
+
+    match link.kind {
+        LinkKind::Page => {
+            let page_id = link.page_id().unwrap_or_default();
+            match mxfile.diagrams.iter().any(|d| d.id == page_id) {
+                true => Ok(()),
+                false => Err("unknown page".to_string()),
+            }
+        }
+        // Actions and unresolved placeholders can't be checked
+        LinkKind::Action | LinkKind::Placeholder => Ok(()),
+        LinkKind::External => check_external_link(&link.url, path, root, mxfile, options),
+    }
+}
+
+fn check_external_link(
+    link: &str,
+    path: &Path,
+    root: &Path,
+    mxfile: &Mxfile,
+    options: &LinksCheckOptions<'_>,
+) -> std::result::Result<(), String> {
+    if let Some(anchor) = link.strip_prefix('#') {
+        return match has_anchor(mxfile, anchor) {
+            true => Ok(()),
+            false => Err("unknown anchor".to_string()),
+        };
+    }
+
+    if link.starts_with("http://") || link.starts_with("https://") {
+        return match options.http_checker {
+            Some(http_checker) => http_checker.check(link),
+            None => Ok(()),
+        };
+    }
+
+    if has_scheme(link) || link.starts_with("//") {
+        // Other kind of links (mailto:, ftp:, ...) and network paths can't be checked
+        return Ok(());
+    }
+
+    let relative_path = link.split(['#', '?']).next().unwrap_or_default();
+    let relative_path = urlencoding::decode(relative_path)
+        .map(|decoded| decoded.into_owned())
+        .unwrap_or_else(|_| relative_path.to_string());
+    let linked_path = match relative_path.strip_prefix('/') {
+        Some(rooted_path) => root.join(rooted_path),
+        None => path.parent().unwrap().join(relative_path),
+    };
+    match linked_path.exists() {
+        true => Ok(()),
+        false => Err("file not found".to_string()),
+    }
+}
+
+fn has_anchor(mxfile: &Mxfile, anchor: &str) -> bool {
+    mxfile.diagrams.iter().any(|diagram| {
+        diagram.id == anchor
+            || diagram.name == anchor
+            // The pages have already been parsed to find their links
+            || diagram.mx_graph_model().is_ok_and(|mx_graph_model| {
+                mx_graph_model.root.elements.iter().any(|element| match element {
+                    Element::MxCell(cell) => cell.id.as_deref() == Some(anchor),
+                    Element::UserObject(user_object) => user_object.id.as_deref() == Some(anchor),
+                    Element::Other => false,
+                })
+            })
+    })
+}
+
+fn has_scheme(link: &str) -> bool {
+    match link.split_once(':') {
+        Some((scheme, _)) => {
+            // A single letter is a windows drive, not a scheme
+            scheme.len() > 1
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,204 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::core::drawio::mxfile::{DiagramSource, Mxfile};
+use crate::core::explorer::filesystem;
+use crate::core::explorer::filesystem::FilterOptions;
+use crate::core::explorer::{ExploreOptions, ExploredFiles};
+use crate::ops::{display_path, input_path, is_safe_filename, keep_file_error, report_file_errors};
+
+pub struct LintOptions<'a> {
+    pub rules: Vec<Rule>,
+    pub storage: StoragePolicy,
+    pub json: bool,
+    pub explore_options: &'a ExploreOptions,
+    pub path: &'a str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rule {
+    EmptyPage,
+    DuplicatePageName,
+    UnsafePageName,
+    LinkMissingLabel,
+    LinkMissingUrl,
+    BrokenPageLink,
+    Storage,
+}
+
+impl Rule {
+    pub fn all() -> Vec<Rule> {
+        vec![
+            Rule::EmptyPage,
+            Rule::DuplicatePageName,
+            Rule::UnsafePageName,
+            Rule::LinkMissingLabel,
+            Rule::LinkMissingUrl,
+            Rule::BrokenPageLink,
+            Rule::Storage,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rule::EmptyPage => "empty-page",
+            Rule::DuplicatePageName => "duplicate-page-name",
+            Rule::UnsafePageName => "unsafe-page-name",
+            Rule::LinkMissingLabel => "link-missing-label",
+            Rule::LinkMissingUrl => "link-missing-url",
+            Rule::BrokenPageLink => "broken-page-link",
+            Rule::Storage => "storage",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Rule> {
+        Rule::all().into_iter().find(|rule| rule.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StoragePolicy {
+    Any,
+    Compressed,
+    Uncompressed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Problem {
+    pub file: String,
+    pub page: Option<usize>,
+    pub page_name: Option<String>,
+    pub rule: &'static str,
+    pub message: String,
+}
+
+pub fn lint(options: LintOptions<'_>) -> Result<()> {
+    let input_path = input_path(options.path)?;
+    let ExploredFiles {
+        files: drawio_files,
+        errors: mut file_errors,
+    } = filesystem::explore_path(
+        &input_path,
+        FilterOptions::no_filtering().with_explore_options(options.explore_options),
+    )
+    .with_context(|| format!("can't explore path {}", &input_path.display()))?;
+    let files_count = drawio_files.len() + file_errors.len();
+
+    let mut problems: Vec<Problem> = vec![];
+    for (path, mxfile) in drawio_files {
+        let drawio_file_path = display_path(options.path, &path);
+        match lint_mxfile(&drawio_file_path, &mxfile, &options) {
+            Ok(file_problems) => problems.extend(file_problems),
+            Err(error) => keep_file_error(
+                path,
+                Err(error),
+                options.explore_options.keep_going,
+                &mut file_errors,
+            )?,
+        }
+    }
+
+    match options.json {
+        true => println!("{}", serde_json::to_string_pretty(&problems)?),
+        false => {
+            for problem in &problems {
+                match (problem.page, &problem.page_name) {
+                    (Some(page), Some(page_name)) => println!(
+                        "{}: page {} '{}': {} [{}]",
+                        problem.file, page, page_name, problem.message, problem.rule
+                    ),
+                    _ => println!("{}: {} [{}]", problem.file, problem.message, problem.rule),
+                }
+            }
+        }
+    }
+
+    let reported = report_file_errors(options.path, &file_errors, files_count);
+    if !problems.is_empty() {
+        anyhow::bail!("lint found {} problem(s)", problems.len());
+    }
+    reported
+}
+
+fn lint_mxfile(file: &str, mxfile: &Mxfile, options: &LintOptions<'_>) -> Result<Vec<Problem>> {
+    let enabled = |rule: Rule| options.rules.contains(&rule);
+    let mut problems: Vec<Problem> = vec![];
+
+    for (position, diagram) in mxfile.diagrams.iter().enumerate() {
+        let mut page_problems: Vec<(Rule, String)> = vec![];
+
+        if enabled(Rule::EmptyPage) && diagram.is_empty()? {
+            page_problems.push((Rule::EmptyPage, "page is empty".to_string()));
+        }
+
+        if enabled(Rule::DuplicatePageName)
+            && mxfile.diagrams[..position]
+                .iter()
+                .any(|previous| previous.name == diagram.name)
+        {
+            page_problems.push((
+                Rule::DuplicatePageName,
+                "page name is already used by another page".to_string(),
+            ));
+        }
+
+        if enabled(Rule::Storage) {
+            // Each page has its own storage, a file can mix compressed and uncompressed pages
+            let message = match (options.storage, diagram.source()) {
+                (StoragePolicy::Compressed, DiagramSource::Plain { .. }) => {
+                    Some("page must be compressed")
+                }
+                (StoragePolicy::Uncompressed, DiagramSource::Compressed(_)) => {
+                    Some("page must be uncompressed")
+                }
+                _ => None,
+            };
+            if let Some(message) = message {
+                page_problems.push((Rule::Storage, message.to_string()));
+            }
+        }
+
+        if enabled(Rule::UnsafePageName) && !is_safe_filename(&diagram.name) {
+            page_problems.push((
+                Rule::UnsafePageName,
+                "page name can't be safely used as a filename".to_string(),
+            ));
+        }
+
+        for link in diagram.get_links()? {
+            if enabled(Rule::LinkMissingLabel) && link.label.is_empty() {
+                page_problems.push((
+                    Rule::LinkMissingLabel,
+                    format!("link to {} has no label", link.url),
+                ));
+            }
+            if enabled(Rule::LinkMissingUrl) && link.url.is_empty() {
+                page_problems.push((
+                    Rule::LinkMissingUrl,
+                    format!("link '{}' has no url", link.label),
+                ));
+            }
+            if let Some(page_id) = link.page_id() {
+                if enabled(Rule::BrokenPageLink) && !mxfile.diagrams.iter().any(|d| d.id == page_id)
+                {
+                    page_problems.push((
+                        Rule::BrokenPageLink,
+                        format!("link '{}' targets an unknown page {}", link.label, page_id),
+                    ));
+                }
+            }
+        }
+
+        for (rule, message) in page_problems {
+            problems.push(Problem {
+                file: file.to_string(),
+                page: Some(position + 1),
+                page_name: Some(diagram.name.clone()),
+                rule: rule.name(),
+                message,
+            });
+        }
+    }
+
+    Ok(problems)
+}
@@ -0,0 +1,167 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::core::drawio::mxfile::Mxfile;
+use crate::core::explorer::filesystem;
+use crate::core::explorer::filesystem::FilterOptions;
+use crate::core::explorer::{ExploreOptions, ExploredFiles};
+use crate::ops::exporter::exported_path;
+use crate::ops::{display_path, input_path, keep_file_error, report_file_errors};
+
+pub struct ListOptions<'a> {
+    pub folder: &'a String,
+    pub format: &'a String,
+    pub remove_page_suffix: bool,
+    pub json: bool,
+    pub explore_options: &'a ExploreOptions,
+    pub path: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PageEntry {
+    pub file: String,
+    pub page: usize,
+    pub name: String,
+    pub id: String,
+    pub cells: usize,
+    pub links: usize,
+    pub export: ExportStatus,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportStatus {
+    Exported,
+    Outdated,
+    Missing,
+}
+
+impl ExportStatus {
+    fn as_str(&self) -> &str {
+        match self {
+            ExportStatus::Exported => "exported",
+            ExportStatus::Outdated => "outdated",
+            ExportStatus::Missing => "missing",
+        }
+    }
+}
+
+pub fn list(options: ListOptions<'_>) -> Result<()> {
+    let input_path = input_path(options.path)?;
+    let ExploredFiles {
+        files: drawio_files,
+        errors: mut file_errors,
+    } = filesystem::explore_path(
+        &input_path,
+        FilterOptions::no_filtering().with_explore_options(options.explore_options),
+    )
+    .with_context(|| format!("can't explore path {}", &input_path.display()))?;
+    let files_count = drawio_files.len() + file_errors.len();
+
+    let mut entries: Vec<PageEntry> = vec![];
+    for (path, mxfile) in drawio_files {
+        let file_entries = page_entries(&path, &mxfile, &options);
+        match file_entries {
+            Ok(file_entries) => entries.extend(file_entries),
+            Err(error) => keep_file_error(
+                path,
+                Err(error),
+                options.explore_options.keep_going,
+                &mut file_errors,
+            )?,
+        }
+    }
+
+    match options.json {
+        true => println!("{}", serde_json::to_string_pretty(&entries)?),
+        false => print_table(&entries),
+    }
+
+    report_file_errors(options.path, &file_errors, files_count)
+}
+
+fn page_entries(path: &Path, mxfile: &Mxfile, options: &ListOptions<'_>) -> Result<Vec<PageEntry>> {
+    let with_page_suffix = !(options.remove_page_suffix && mxfile.diagrams.len() == 1);
+    let mut entries: Vec<PageEntry> = vec![];
+    for (position, diagram) in mxfile.diagrams.iter().enumerate() {
+        let mx_graph_model = diagram.mx_graph_model()?;
+        let exported_path = exported_path(
+            path,
+            options.folder,
+            diagram,
+            with_page_suffix,
+            options.format,
+        );
+        entries.push(PageEntry {
+            file: display_path(options.path, path),
+            page: position + 1,
+            name: diagram.name.clone(),
+            id: diagram.id.clone(),
+            cells: mx_graph_model.root.elements.len(),
+            links: mx_graph_model.get_links().len(),
+            export: export_status(path, &exported_path),
+        });
+    }
+    Ok(entries)
+}
+
+fn export_status(path: &Path, exported_path: &Path) -> ExportStatus {
+    let exported_file_modified_date = match fs::metadata(exported_path) {
+        Ok(metadata) => metadata.modified().ok(),
+        Err(_) => return ExportStatus::Missing,
+    };
+    let drawio_file_modified_date = fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (drawio_file_modified_date, exported_file_modified_date) {
+        (Some(drawio_date), Some(exported_date)) if drawio_date > exported_date => {
+            ExportStatus::Outdated
+        }
+        _ => ExportStatus::Exported,
+    }
+}
+
+fn print_table(entries: &[PageEntry]) {
+    if entries.is_empty() {
+        return;
+    }
+
+    let headers = ["FILE", "PAGE", "NAME", "ID", "CELLS", "LINKS", "EXPORT"];
+    let rows: Vec<[String; 7]> = entries
+        .iter()
+        .map(|entry| {
+            [
+                entry.file.clone(),
+                entry.page.to_string(),
+                entry.name.clone(),
+                entry.id.clone(),
+                entry.cells.to_string(),
+                entry.links.to_string(),
+                entry.export.as_str().to_string(),
+            ]
+        })
+        .collect();
+
+    let mut widths = headers.map(|header| header.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: Vec<&str>| -> String {
+        cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    println!("{}", format_row(headers.to_vec()));
+    for row in &rows {
+        println!("{}", format_row(row.iter().map(|c| c.as_str()).collect()));
+    }
+}
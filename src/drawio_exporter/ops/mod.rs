@@ -1 +1,112 @@
+use anyhow::{anyhow, Context, Result};
+use relative_path::RelativePath;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::core::drawio::mxfile::Mxfile;
+use crate::core::explorer::FileError;
+
+pub mod book;
+pub mod clean;
+pub mod diff;
 pub mod exporter;
+pub mod formatter;
+pub mod links;
+pub mod lint;
+pub mod list;
+pub mod normalize;
+pub mod split;
+pub mod text;
+
+/// Exit code when some files failed, but the other ones have been processed
+pub const PARTIAL_FAILURE_EXIT_CODE: i32 = 3;
+
+#[derive(Debug)]
+pub struct PartialFailure {
+    pub failed: usize,
+    pub total: usize,
+}
+
+impl fmt::Display for PartialFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} file(s) failed", self.failed, self.total)
+    }
+}
+
+impl std::error::Error for PartialFailure {}
+
+fn input_path(path: &str) -> Result<PathBuf> {
+    // Fallback in case of empty path, we take the current directory
+    let input_path = match path {
+        "" => PathBuf::from("."),
+        path => PathBuf::from(path),
+    };
+    if !input_path.exists() {
+        return Err(anyhow!(format!(
+            "path '{}' must exist (as directory or file)",
+            path
+        )));
+    }
+    Ok(input_path)
+}
+
+fn display_path(base: &str, path: &Path) -> String {
+    RelativePath::new(base)
+        .relative(RelativePath::new(path.to_str().unwrap()))
+        .to_string()
+}
+
+fn resolve_placeholders(
+    path: &Path,
+    mxfile: &mut Mxfile,
+    variables: &BTreeMap<String, String>,
+) -> Result<()> {
+    let filename = path.file_name().unwrap().to_str().unwrap();
+    mxfile.resolve_placeholders(filename, variables)
+}
+
+/// Check that a name, like a page name, can be used in a filename on every platform
+fn is_safe_filename(name: &str) -> bool {
+    !name.is_empty()
+        && name.trim() == name
+        && !name.starts_with('.')
+        && !name
+            .chars()
+            .any(|c| c.is_control() || "/\\:*?\"<>|".contains(c))
+}
+
+/// Keep the error of a file when keeping going, instead of stopping on it
+fn keep_file_error(
+    path: PathBuf,
+    processed: Result<()>,
+    keep_going: bool,
+    file_errors: &mut Vec<FileError>,
+) -> Result<()> {
+    match processed {
+        Err(error) if keep_going => {
+            file_errors.push(FileError { path, error });
+            Ok(())
+        }
+        processed => processed.with_context(|| format!("can't process {}", path.display())),
+    }
+}
+
+fn report_file_errors(base: &str, file_errors: &[FileError], files_count: usize) -> Result<()> {
+    if file_errors.is_empty() {
+        return Ok(());
+    }
+    eprintln!("+ failed files :");
+    for file_error in file_errors {
+        eprintln!(
+            "- {} : {:#}",
+            display_path(base, &file_error.path),
+            file_error.error
+        );
+    }
+    Err(PartialFailure {
+        failed: file_errors.len(),
+        total: files_count,
+    }
+    .into())
+}
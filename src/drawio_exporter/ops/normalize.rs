@@ -0,0 +1,72 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+use crate::core::drawio::mxfile::{is_embedded, Mxfile};
+use crate::core::drawio::writer::{write_mxfile, WriteOptions};
+use crate::core::explorer::filesystem;
+use crate::core::explorer::filesystem::FilterOptions;
+use crate::core::explorer::{ExploreOptions, ExploredFiles};
+use crate::ops::{display_path, input_path, keep_file_error, report_file_errors};
+
+pub struct NormalizeOptions<'a> {
+    pub compressed: bool,
+    pub explore_options: &'a ExploreOptions,
+    pub path: &'a str,
+}
+
+pub fn normalize(options: NormalizeOptions<'_>) -> Result<()> {
+    let input_path = input_path(options.path)?;
+    let ExploredFiles {
+        files: drawio_files,
+        errors: mut file_errors,
+    } = filesystem::explore_path(
+        &input_path,
+        FilterOptions::no_filtering().with_explore_options(options.explore_options),
+    )
+    .with_context(|| format!("can't explore path {}", &input_path.display()))?;
+    let files_count = drawio_files.len() + file_errors.len();
+
+    for (path, mxfile) in drawio_files {
+        let drawio_file_path = display_path(options.path, &path);
+        if is_embedded(&path) {
+            println!("- skip file : {} (embedded diagram)", drawio_file_path);
+            continue;
+        }
+        let write_options = WriteOptions {
+            compressed: options.compressed,
+            formatted: false,
+        };
+        match rewrite_file(&path, &mxfile, &write_options, true) {
+            Ok(true) => println!("+ normalize file : {}", drawio_file_path),
+            Ok(false) => {}
+            Err(error) => keep_file_error(
+                path,
+                Err(error),
+                options.explore_options.keep_going,
+                &mut file_errors,
+            )?,
+        }
+    }
+
+    report_file_errors(options.path, &file_errors, files_count)
+}
+
+/// Tell if the content of the file changes when it's written back,
+/// and rewrite it (unless only checking it)
+pub(crate) fn rewrite_file(
+    path: &Path,
+    mxfile: &Mxfile,
+    options: &WriteOptions,
+    write: bool,
+) -> Result<bool> {
+    let content = write_mxfile(mxfile, options)?;
+    let current_content = fs::read_to_string(path).unwrap_or_default();
+    if current_content == content {
+        return Ok(false);
+    }
+    if write {
+        fs::write(path, content).with_context(|| format!("can write {}", path.display()))?;
+    }
+    Ok(true)
+}
@@ -0,0 +1,170 @@
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::drawio::mxfile::{drawio_file_stem, is_embedded, read_file, Mxfile};
+use crate::core::drawio::pages;
+use crate::core::drawio::writer::{write_file, WriteOptions};
+use crate::core::explorer::filesystem;
+use crate::core::explorer::filesystem::FilterOptions;
+use crate::core::explorer::{ExploreOptions, ExploredFiles};
+use crate::ops::exporter::page_suffix;
+use crate::ops::{display_path, input_path, is_safe_filename, keep_file_error, report_file_errors};
+
+pub struct SplitOptions<'a> {
+    pub folder: Option<&'a String>,
+    pub explore_options: &'a ExploreOptions,
+    pub path: &'a str,
+}
+
+pub fn split(options: SplitOptions<'_>) -> Result<()> {
+    let input_path = input_path(options.path)?;
+    let ExploredFiles {
+        files: drawio_files,
+        errors: mut file_errors,
+    } = filesystem::explore_path(
+        &input_path,
+        FilterOptions::no_filtering().with_explore_options(options.explore_options),
+    )
+    .with_context(|| format!("can't explore path {}", &input_path.display()))?;
+    let files_count = drawio_files.len() + file_errors.len();
+
+    for (path, mxfile) in drawio_files {
+        if mxfile.diagrams.len() < 2 {
+            continue;
+        }
+        println!("+ split file : {}", display_path(options.path, &path));
+        let splitted = split_file(&path, &mxfile, &options);
+        keep_file_error(
+            path,
+            splitted,
+            options.explore_options.keep_going,
+            &mut file_errors,
+        )?;
+    }
+
+    report_file_errors(options.path, &file_errors, files_count)
+}
+
+fn split_file(path: &Path, mxfile: &Mxfile, options: &SplitOptions<'_>) -> Result<()> {
+    // The page names are part of the filenames, so they must not change their folder
+    if let Some(diagram) = mxfile
+        .diagrams
+        .iter()
+        .find(|diagram| !is_safe_filename(&diagram.name))
+    {
+        anyhow::bail!(
+            "can't split page '{}', its name can't be safely used as a filename",
+            diagram.name
+        );
+    }
+    let folder = match options.folder {
+        Some(folder) => path.parent().unwrap().join(folder),
+        None => path.parent().unwrap().to_path_buf(),
+    };
+    let page_paths: Vec<PathBuf> = mxfile
+        .diagrams
+        .iter()
+        .map(|diagram| {
+            folder.join(format!(
+                "{}{}.drawio",
+                drawio_file_stem(path),
+                page_suffix(diagram, true)
+            ))
+        })
+        .collect();
+    // Pages with the same name (or only differing by spaces and dashes) would overwrite each other
+    for (position, page_path) in page_paths.iter().enumerate() {
+        if let Some(other_position) = page_paths[..position]
+            .iter()
+            .position(|other_page_path| other_page_path == page_path)
+        {
+            anyhow::bail!(
+                "can't split pages '{}' and '{}', they would both be written in {}",
+                mxfile.diagrams[other_position].name,
+                mxfile.diagrams[position].name,
+                display_path(options.path, page_path)
+            );
+        }
+    }
+    fs::create_dir_all(&folder)
+        .with_context(|| format!("can't prepare folder {}", folder.display()))?;
+
+    for ((diagram, page_mxfile), page_path) in mxfile
+        .diagrams
+        .iter()
+        .zip(pages::split(mxfile))
+        .zip(page_paths)
+    {
+        println!(
+            "- write page {} : {}",
+            diagram.name,
+            display_path(options.path, &page_path)
+        );
+        // The other pages are now in other files, so the links to them can't work anymore
+        for link in diagram.get_links()? {
+            if let Some(page_id) = link.page_id().filter(|page_id| *page_id != diagram.id) {
+                println!(
+                    "warn: page link '{}' targets page {} which is now in another file",
+                    link.label, page_id
+                );
+            }
+        }
+        write_file(
+            &page_path,
+            &page_mxfile,
+            &WriteOptions {
+                compressed: mxfile.compressed,
+                formatted: false,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+pub struct MergeOptions<'a> {
+    pub output: &'a String,
+    pub paths: Vec<&'a String>,
+}
+
+pub fn merge(options: MergeOptions<'_>) -> Result<()> {
+    let output_path = PathBuf::from(options.output);
+    if is_embedded(&output_path) {
+        return Err(anyhow!(
+            "can't merge into {}, diagrams can't be embedded in an image",
+            options.output
+        ));
+    }
+
+    let mut mxfiles: Vec<Mxfile> = vec![];
+    for path in &options.paths {
+        println!("+ merge file : {}", path);
+        let mxfile = read_file(Path::new(path)).with_context(|| format!("can't read {}", path))?;
+        mxfiles.push(mxfile);
+    }
+
+    let merged_mxfile = pages::merge(&mxfiles)?;
+    // The merged pages are in the same order than the pages of the files
+    let diagrams = mxfiles
+        .iter()
+        .zip(&options.paths)
+        .flat_map(|(mxfile, path)| mxfile.diagrams.iter().map(move |diagram| (diagram, path)));
+    for ((diagram, path), merged_diagram) in diagrams.zip(&merged_mxfile.diagrams) {
+        if diagram.id != merged_diagram.id {
+            println!(
+                "warn: page {} of {} already used id {}, now {}",
+                diagram.name, path, diagram.id, merged_diagram.id
+            );
+        }
+    }
+
+    println!("+ write file : {}", options.output);
+    write_file(
+        &output_path,
+        &merged_mxfile,
+        &WriteOptions {
+            compressed: merged_mxfile.compressed,
+            formatted: false,
+        },
+    )
+}
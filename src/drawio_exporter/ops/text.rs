@@ -0,0 +1,98 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+
+use crate::core::drawio::mxfile::{Diagram, Mxfile, TextContent};
+use crate::core::explorer::filesystem;
+use crate::core::explorer::filesystem::FilterOptions;
+use crate::core::explorer::{ExploreOptions, ExploredFiles};
+use crate::ops::{
+    display_path, input_path, keep_file_error, report_file_errors, resolve_placeholders,
+};
+
+pub struct TextOptions<'a> {
+    pub variables: &'a BTreeMap<String, String>,
+    pub explore_options: &'a ExploreOptions,
+    pub path: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchIndexEntry {
+    pub file: String,
+    pub page: usize,
+    pub name: String,
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub export: Option<String>,
+    #[serde(flatten)]
+    pub content: TextContent,
+}
+
+impl SearchIndexEntry {
+    pub fn new(file: String, position: usize, diagram: &Diagram) -> Result<SearchIndexEntry> {
+        Ok(SearchIndexEntry {
+            file,
+            page: position + 1,
+            name: diagram.name.clone(),
+            id: diagram.id.clone(),
+            export: None,
+            content: diagram.get_text_content()?,
+        })
+    }
+}
+
+pub fn text(options: TextOptions<'_>) -> Result<()> {
+    let input_path = input_path(options.path)?;
+    let ExploredFiles {
+        files: drawio_files,
+        errors: mut file_errors,
+    } = filesystem::explore_path(
+        &input_path,
+        FilterOptions::no_filtering().with_explore_options(options.explore_options),
+    )
+    .with_context(|| format!("can't explore path {}", &input_path.display()))?;
+    let files_count = drawio_files.len() + file_errors.len();
+
+    let mut entries: Vec<SearchIndexEntry> = vec![];
+    for (path, mut mxfile) in drawio_files {
+        let file_entries = file_entries(&path, &mut mxfile, &options);
+        match file_entries {
+            Ok(file_entries) => entries.extend(file_entries),
+            Err(error) => keep_file_error(
+                path,
+                Err(error),
+                options.explore_options.keep_going,
+                &mut file_errors,
+            )?,
+        }
+    }
+
+    println!("{}", serde_json::to_string_pretty(&entries)?);
+    report_file_errors(options.path, &file_errors, files_count)
+}
+
+fn file_entries(
+    path: &Path,
+    mxfile: &mut Mxfile,
+    options: &TextOptions<'_>,
+) -> Result<Vec<SearchIndexEntry>> {
+    resolve_placeholders(path, mxfile, options.variables)?;
+    mxfile
+        .diagrams
+        .iter()
+        .enumerate()
+        .map(|(position, diagram)| {
+            SearchIndexEntry::new(display_path(options.path, path), position, diagram)
+        })
+        .collect()
+}
+
+pub(crate) fn write_search_index(path: &Path, entries: &[SearchIndexEntry]) -> Result<()> {
+    let file = File::create(path)
+        .with_context(|| format!("can't create search index {}", path.display()))?;
+    serde_json::to_writer_pretty(file, entries)
+        .with_context(|| format!("can't write search index {}", path.display()))?;
+    Ok(())
+}
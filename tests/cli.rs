@@ -7,8 +7,11 @@ use assert_cmd::prelude::*;
 use fs_extra::{copy_items, dir};
 use std::fs;
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
 use tempfile::{tempdir, tempdir_in};
 
 pub struct DrawioExporterCommand {
@@ -16,6 +19,76 @@ pub struct DrawioExporterCommand {
     pub current_dir: PathBuf,
 }
 
+/// Path of a fake Draw.io Desktop application, to export without draw.io
+pub fn fake_drawio_application() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/bin/fake-drawio")
+}
+
+/// Path of a fake Xvfb, to use a virtual display without Xvfb
+pub fn fake_xvfb() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/bin/fake-xvfb")
+}
+
+/// Check if a process is still running (a killed process can remain as a zombie until it's reaped)
+pub fn is_process_running(pid: &str) -> Result<bool> {
+    let process = Command::new("ps")
+        .args(["-o", "stat=", "-p", pid.trim()])
+        .output()?;
+    let state = String::from_utf8(process.stdout)?;
+    Ok(!state.trim().is_empty() && !state.starts_with('Z'))
+}
+
+/// Local http server answering each request with the status and the body returned for its path,
+/// like `("200 OK", body)`, and sending back the bodies of the requests.
+/// Return the address of the server (like `127.0.0.1:1234`)
+pub fn mock_http_server(
+    respond: impl Fn(&str) -> (&'static str, String) + Send + 'static,
+) -> Result<(String, Receiver<String>)> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = listener.local_addr()?.to_string();
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let Some((path, body)) = read_request(&stream) else {
+                continue;
+            };
+            let (status, response_body) = respond(&path);
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                response_body.len(),
+                response_body
+            );
+            // The requests are only received by the tests checking them
+            let _ = sender.send(body);
+        }
+    });
+    Ok((address, receiver))
+}
+
+/// Path and body of a request
+fn read_request(stream: &TcpStream) -> Option<(String, String)> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+    let path = request_line.split_whitespace().nth(1)?.to_string();
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        if line == "\r\n" || line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+            content_length = value.trim().parse().ok()?;
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).ok()?;
+    Some((path, String::from_utf8_lossy(&body).into_owned()))
+}
+
 impl DrawioExporterCommand {
     pub fn new_file(&self, name: &str, content: &str) -> Result<()> {
         let mut file = File::create(self.current_dir.join(name))?;
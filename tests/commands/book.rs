@@ -0,0 +1,174 @@
+use crate::{fake_drawio_application, DrawioExporterCommand};
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use lopdf::content::Content;
+use lopdf::{Document, Object, ObjectId};
+use predicates::prelude::predicate::str::contains;
+use std::fs;
+
+/// Titles of the outline items, and of their children
+fn outline(document: &Document, first: Option<ObjectId>) -> Result<Vec<(String, Vec<String>)>> {
+    let mut items = vec![];
+    let mut item_id = first;
+    while let Some(id) = item_id {
+        let item = document.get_dictionary(id)?;
+        let title = String::from_utf8_lossy(item.get(b"Title")?.as_str()?).into_owned();
+        let first_child = item.get(b"First").and_then(Object::as_reference).ok();
+        let children = outline(document, first_child)?
+            .into_iter()
+            .map(|(title, _)| title)
+            .collect();
+        items.push((title, children));
+        item_id = item.get(b"Next").and_then(Object::as_reference).ok();
+    }
+    Ok(items)
+}
+
+#[cfg(unix)]
+#[test]
+fn book_of_exported_pages() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    fs::create_dir(drawio_exporter.current_dir.join("a"))?;
+    fs::copy(
+        "tests/data/single_page/single-page.drawio",
+        drawio_exporter.current_dir.join("a/single-page.drawio"),
+    )?;
+    let book_file = drawio_exporter.current_dir.join("book.pdf");
+
+    drawio_exporter
+        .cmd
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+
+    let output = "+ add file : a/single-page.drawio
++ add file : types/nominal.drawio
++ write book : ";
+
+    drawio_exporter.new_cmd()?;
+    drawio_exporter
+        .cmd
+        .arg("book")
+        .arg("--file")
+        .arg(&book_file)
+        .arg("--title")
+        .arg("Architecture review")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(output));
+
+    let book = Document::load(&book_file)?;
+    let pages = book.get_pages();
+    // Cover, table of contents, and the exported pages
+    assert_eq!(pages.len(), 5);
+    assert!(String::from_utf8_lossy(&book.get_page_content(pages[&1])?)
+        .contains("(Architecture review)"));
+    let table_of_contents =
+        String::from_utf8_lossy(&book.get_page_content(pages[&2])?).into_owned();
+    assert!(table_of_contents.contains("(types/nominal.drawio)"));
+    assert!(table_of_contents.contains("(Page 2)"));
+
+    // The size of the exported pages is inherited from their page tree
+    let exported_page = book.get_dictionary(pages[&3])?;
+    let media_box = exported_page
+        .get(b"MediaBox")?
+        .as_array()?
+        .iter()
+        .map(Object::as_i64)
+        .collect::<Result<Vec<i64>, _>>()?;
+    assert_eq!(media_box, vec![0, 0, 200, 100]);
+
+    let catalog = book.catalog()?;
+    let outlines = book.get_dictionary(catalog.get(b"Outlines")?.as_reference()?)?;
+    let first = outlines.get(b"First")?.as_reference().ok();
+    assert_eq!(
+        outline(&book, first)?,
+        vec![
+            ("Table of contents".to_string(), vec![]),
+            (
+                "a/single-page.drawio".to_string(),
+                vec!["Page-1".to_string()]
+            ),
+            (
+                "types/nominal.drawio".to_string(),
+                vec!["Page-1".to_string(), "Page 2".to_string()]
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn book_with_long_title() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let book_file = drawio_exporter.current_dir.join("book.pdf");
+    let title = "Architecture review of the payment platform, with all its services and databases";
+
+    drawio_exporter
+        .cmd
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+
+    drawio_exporter.new_cmd()?;
+    drawio_exporter
+        .cmd
+        .arg("book")
+        .arg("--file")
+        .arg(&book_file)
+        .arg("--title")
+        .arg(title)
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+
+    // The title is the first text of the cover, shortened to fit in the width of the page
+    let book = Document::load(&book_file)?;
+    let cover = Content::decode(&book.get_page_content(book.get_pages()[&1])?)?;
+    let operand = |operator: &str, index: usize| {
+        cover
+            .operations
+            .iter()
+            .find(|operation| operation.operator == operator)
+            .map(|operation| operation.operands[index].clone())
+    };
+    let size = operand("Tf", 1).unwrap().as_float()?;
+    let x = operand("Td", 0).unwrap().as_float()?;
+    let written_title = String::from_utf8(operand("Tj", 0).unwrap().as_str()?.to_vec())?;
+    assert!(written_title.len() < title.len());
+    assert!(written_title.ends_with("..."));
+    // Helvetica characters are about 0.556 times as wide as the font size
+    let width = written_title.len() as f32 * size * 0.556;
+    assert!(x >= 0.0 && x + width <= 595.0, "x={} width={}", x, width);
+
+    Ok(())
+}
+
+#[test]
+fn book_without_exported_pages() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let book_file = drawio_exporter.current_dir.join("book.pdf");
+
+    drawio_exporter
+        .cmd
+        .arg("book")
+        .arg("--file")
+        .arg(&book_file)
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "can't find types/export/nominal-Page-1.pdf for page 1 : Page-1, export the drawio files in pdf format first",
+        ));
+
+    assert!(!book_file.exists());
+
+    Ok(())
+}
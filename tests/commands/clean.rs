@@ -0,0 +1,112 @@
+use crate::DrawioExporterCommand;
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicates::prelude::predicate::str::contains;
+use std::fs;
+
+#[test]
+fn clean_export_folders() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("tree", true)?;
+    drawio_exporter.new_file_in_folder("tree/export", "file1-Page-1.pdf", "")?;
+    drawio_exporter.new_file_in_folder("tree/folder1/export", "file2.1-Page-1.pdf", "")?;
+
+    let output = "- remove folder : tree/export
+- remove folder : tree/folder1/export";
+
+    drawio_exporter
+        .cmd
+        .arg("clean")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(output));
+
+    assert!(!drawio_exporter.current_dir.join("tree/export").exists());
+    assert!(!drawio_exporter
+        .current_dir
+        .join("tree/folder1/export")
+        .exists());
+    assert!(drawio_exporter
+        .current_dir
+        .join("tree/file1.drawio")
+        .exists());
+
+    Ok(())
+}
+
+#[test]
+fn clean_custom_export_folders() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("tree", true)?;
+    drawio_exporter.new_file_in_folder("tree/export", "file1-Page-1.pdf", "")?;
+    drawio_exporter.new_file_in_folder("tree/output", "file1-Page-1.pdf", "")?;
+
+    drawio_exporter
+        .cmd
+        .arg("clean")
+        .arg("--output")
+        .arg("output")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout("- remove folder : tree/output\n");
+
+    assert!(fs::metadata(drawio_exporter.current_dir.join("tree/export"))?.is_dir());
+
+    Ok(())
+}
+
+#[test]
+fn clean_export_folders_outside_of_the_drawio_files_folders() -> Result<()> {
+    for folder in [
+        ".",
+        "..",
+        "../export",
+        "folder/export",
+        "folder\\export",
+        "/tmp",
+    ] {
+        let mut drawio_exporter = DrawioExporterCommand::new_using_data("tree", true)?;
+
+        drawio_exporter
+            .cmd
+            .arg("clean")
+            .arg("--output")
+            .arg(folder)
+            .arg(&drawio_exporter.current_dir)
+            .assert()
+            .failure()
+            .stderr(contains(format!(
+                "export folder '{}' must be a folder name, without any path",
+                folder
+            )));
+
+        assert!(drawio_exporter
+            .current_dir
+            .join("tree/file1.drawio")
+            .exists());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn clean_export_folder_containing_drawio_files() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("tree", true)?;
+    drawio_exporter.new_file_in_folder("tree/export", "file1-Page-1.pdf", "")?;
+
+    drawio_exporter
+        .cmd
+        .arg("clean")
+        .arg("--output")
+        .arg("folder1")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "can't remove export folder tree/folder1, it contains the drawio file tree/folder1/",
+        ));
+
+    assert!(drawio_exporter.current_dir.join("tree/folder1").is_dir());
+
+    Ok(())
+}
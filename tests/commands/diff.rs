@@ -0,0 +1,92 @@
+use crate::DrawioExporterCommand;
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use git2::{Repository, Signature};
+use predicates::prelude::predicate::str::contains;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn diff_pages_since_git_reference() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("tree", false)?;
+    commit_all(&drawio_exporter.current_dir)?;
+
+    fs::copy(
+        "tests/data/links/links.drawio",
+        drawio_exporter.current_dir.join("tree/file1.drawio"),
+    )?;
+    stage_all(&drawio_exporter.current_dir)?;
+
+    let output = "+ diff file : tree/file1.drawio
+- page added : Page-1
+- page added : Page-2
+- page added : empty-link
+- page added : empty-text
+- page removed : Page-1
+- page removed : Page 2";
+
+    drawio_exporter
+        .cmd
+        .arg("diff")
+        .arg("--git-ref")
+        .arg("HEAD")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(output));
+
+    Ok(())
+}
+
+#[test]
+fn diff_pages_with_geometry_or_style_changes() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("split", false)?;
+    commit_all(&drawio_exporter.current_dir)?;
+
+    // Only the first page changes, its shape is moved and its style changed
+    let book = drawio_exporter.current_dir.join("split/book.drawio");
+    let content = fs::read_to_string(&book)?
+        .replacen(
+            "<mxGeometry width=\"120\"",
+            "<mxGeometry x=\"500\" width=\"120\"",
+            1,
+        )
+        .replacen(
+            "style=\"rounded=1;\"",
+            "style=\"rounded=1;fillColor=#f00;\"",
+            1,
+        );
+    fs::write(&book, content)?;
+    stage_all(&drawio_exporter.current_dir)?;
+
+    drawio_exporter
+        .cmd
+        .arg("diff")
+        .arg("--git-ref")
+        .arg("HEAD")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout("+ diff file : split/book.drawio\n- page changed : Intro\n");
+
+    Ok(())
+}
+
+fn stage_all(path: &Path) -> Result<Repository> {
+    let repo = Repository::open(path)?;
+    let mut index = repo.index()?;
+    index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
+    index.write()?;
+    drop(index);
+    Ok(repo)
+}
+
+fn commit_all(path: &Path) -> Result<()> {
+    Repository::init(path)?;
+    let repo = stage_all(path)?;
+    let mut index = repo.index()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let signature = Signature::now("drawio-exporter", "drawio-exporter@example.com")?;
+    repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])?;
+    Ok(())
+}
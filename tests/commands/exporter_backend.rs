@@ -0,0 +1,165 @@
+use crate::{mock_http_server, DrawioExporterCommand};
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use drawio_exporter::core::drawio::export_backend::{ExportCall, FakeBackend};
+use drawio_exporter::core::explorer::ExploreOptions;
+use drawio_exporter::core::post_processing::PostProcessing;
+use drawio_exporter::ops::exporter::{exporter, ExporterOptions};
+use predicates::prelude::predicate::str::contains;
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+const EXPORTED_SVG: &str =
+    r#"<svg xmlns="http://www.w3.org/2000/svg" width="10px" height="10px"/>"#;
+
+/// Answer the export requests with the exported content, and send back their bodies
+fn mock_export_server(exported: &'static str) -> Result<(String, Receiver<String>)> {
+    let (address, requests) = mock_http_server(|_| ("200 OK", exported.to_string()))?;
+    Ok((format!("http://{}/", address), requests))
+}
+
+#[test]
+fn export_with_export_server() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let (url, requests) = mock_export_server(EXPORTED_SVG)?;
+
+    let output = "+ export file : types/nominal.drawio
+- export page 1 : Page-1
+\\ generate svg file
+- export page 2 : Page 2
+\\ generate svg file";
+
+    drawio_exporter
+        .cmd
+        .arg("--export-server")
+        .arg(&url)
+        .arg("--format")
+        .arg("svg")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(output));
+
+    let first_request = requests.recv()?;
+    assert!(first_request.contains("format=svg"));
+    assert!(first_request.contains("from=0"));
+    assert!(first_request.contains("xml=%3Cmxfile"));
+    assert!(requests.recv()?.contains("from=1"));
+
+    let export_folder = drawio_exporter.current_dir.join("types/export");
+    assert_eq!(
+        fs::read_to_string(export_folder.join("nominal-Page-1.svg"))?,
+        EXPORTED_SVG
+    );
+    assert_eq!(
+        fs::read_to_string(export_folder.join("nominal-Page-2.svg"))?,
+        EXPORTED_SVG
+    );
+
+    Ok(())
+}
+
+#[test]
+fn export_with_export_server_answering_an_invalid_file() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let (url, _) = mock_export_server("<svg><g></g>")?;
+
+    drawio_exporter
+        .cmd
+        .arg("--export-server")
+        .arg(&url)
+        .arg("--format")
+        .arg("svg")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains("can't export page 1 : Page-1"))
+        .stderr(contains(
+            "nominal-Page-1.svg is truncated (unclosed xml elements)",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn export_with_export_server_in_unsupported_format() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("--export-server")
+        .arg("http://127.0.0.1:1/")
+        .arg("--format")
+        .arg("vsdx")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains("vsdx format isn't supported by the export server"));
+
+    Ok(())
+}
+
+#[test]
+fn export_with_fake_backend() -> Result<()> {
+    let drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let backend = FakeBackend::new();
+    let path = drawio_exporter.current_dir.to_str().unwrap();
+    let folder = "export".to_string();
+    let format = "svg".to_string();
+    let border = "0".to_string();
+    let quality = "90".to_string();
+
+    exporter(ExporterOptions {
+        backend: &backend,
+        retries: 0,
+        retry_delay: Duration::ZERO,
+        folder: &folder,
+        on_filesystem_changes: false,
+        on_git_changes_since_reference: None,
+        remove_page_suffix: false,
+        explore_options: &ExploreOptions::default(),
+        path,
+        format: &format,
+        border: &border,
+        scale: None,
+        enable_plugins: false,
+        width: None,
+        height: None,
+        crop: false,
+        transparent: false,
+        quality: &quality,
+        uncompressed: false,
+        embed_svg_images: false,
+        embed_diagram: false,
+        batch: false,
+        single_pdf: false,
+        page_range: None,
+        post_processing: &PostProcessing::new(),
+        search_index: None,
+        text_alternative: false,
+        variables: &BTreeMap::new(),
+    })?;
+
+    let input = drawio_exporter.current_dir.join("types/nominal.drawio");
+    let export_folder = drawio_exporter.current_dir.join("types/export");
+    let call = |page_index: &str, output: &str| ExportCall {
+        input: input.to_str().unwrap().to_string(),
+        output: Some(export_folder.join(output).to_str().unwrap().to_string()),
+        format: "svg".to_string(),
+        page_index: Some(page_index.to_string()),
+        page_range: None,
+        all_pages: false,
+    };
+    assert_eq!(
+        backend.calls(),
+        vec![
+            call("0", "nominal-Page-1.svg"),
+            call("1", "nominal-Page-2.svg")
+        ]
+    );
+    assert!(fs::read_to_string(export_folder.join("nominal-Page-1.svg"))?.starts_with("<svg"));
+
+    Ok(())
+}
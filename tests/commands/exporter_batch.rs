@@ -0,0 +1,99 @@
+use crate::{fake_drawio_application, DrawioExporterCommand};
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicates::prelude::predicate::str::contains;
+use std::fs;
+
+#[cfg(unix)]
+#[test]
+fn export_each_page_with_its_own_execution() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--format")
+        .arg("png")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+
+    assert_eq!(fs::read_to_string(&log_file)?.lines().count(), 2);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_all_pages_in_batch() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    let output = "+ export file : types/nominal.drawio
+\\ generate png files of all pages
+- export page 1 : Page-1
+\\ generate adoc file
+\\ include links in adoc file
+- export page 2 : Page 2
+\\ generate adoc file
+\\ include links in adoc file";
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        // The display is prepared on the first export, so it's kept out of the output
+        .arg("--virtual-display")
+        .arg("off")
+        .arg("--batch")
+        .arg("--format")
+        .arg("adoc")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(output));
+
+    assert_eq!(fs::read_to_string(&log_file)?.lines().count(), 1);
+    let export_folder = drawio_exporter.current_dir.join("types/export");
+    let mut exported_files = fs::read_dir(&export_folder)?
+        .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+        .collect::<Result<Vec<String>>>()?;
+    exported_files.sort();
+    assert_eq!(
+        exported_files,
+        vec![
+            "nominal-Page-1.adoc",
+            "nominal-Page-1.png",
+            "nominal-Page-2.adoc",
+            "nominal-Page-2.png",
+        ]
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_in_batch_only_for_image_formats() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--batch")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains("\\ generate pdf file"));
+
+    assert_eq!(fs::read_to_string(&log_file)?.lines().count(), 2);
+
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use crate::{fake_drawio_application, DrawioExporterCommand};
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicates::prelude::predicate::str::contains;
+use std::env;
+use std::fs;
+
+#[cfg(unix)]
+#[test]
+fn export_with_application_from_env_variable() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    drawio_exporter
+        .cmd
+        .env_remove("DRAWIO_EXPORTER_APPLICATION")
+        .env("DRAWIO_DESKTOP_EXECUTABLE_PATH", fake_drawio_application())
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+
+    assert!(drawio_exporter
+        .current_dir
+        .join("types/export/nominal-Page-1.pdf")
+        .exists());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_with_application_from_path() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let bin_folder = drawio_exporter.current_dir.join("bin");
+    fs::create_dir(&bin_folder)?;
+    std::os::unix::fs::symlink(fake_drawio_application(), bin_folder.join("drawio"))?;
+    let path = env::join_paths(
+        [bin_folder]
+            .into_iter()
+            .chain(env::split_paths(&env::var_os("PATH").unwrap_or_default())),
+    )?;
+
+    drawio_exporter
+        .cmd
+        .env_remove("DRAWIO_EXPORTER_APPLICATION")
+        .env_remove("DRAWIO_DESKTOP_EXECUTABLE_PATH")
+        .env("PATH", path)
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+
+    assert!(drawio_exporter
+        .current_dir
+        .join("types/export/nominal-Page-1.pdf")
+        .exists());
+
+    Ok(())
+}
+
+#[test]
+fn export_with_missing_application_from_env_variable() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    drawio_exporter
+        .cmd
+        .env("DRAWIO_EXPORTER_APPLICATION", "/missing/drawio")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "Draw.io Desktop application path '/missing/drawio' set by DRAWIO_EXPORTER_APPLICATION don't exists",
+        ));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_rejects_option_unsupported_by_draw_io_version() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .env("FAKE_DRAWIO_VERSION", "16.0.0")
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--enable-plugins")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "--enable-plugins option needs draw.io desktop 19.0.3 or later, but 16.0.0 is installed",
+        ));
+
+    // Nothing is exported
+    assert!(!log_file.exists());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_accepts_option_supported_by_draw_io_version() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_VERSION", "19.0.3")
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--format")
+        .arg("svg")
+        .arg("--embed-diagram")
+        .arg("--enable-plugins")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_nothing_without_running_draw_io() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("empty_folder", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env_remove("DISPLAY")
+        .env_remove("WAYLAND_DISPLAY")
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--embed-svg-images")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(""); // No Output
+
+    // Neither its version nor an export was needed
+    assert!(!log_file.exists());
+
+    Ok(())
+}
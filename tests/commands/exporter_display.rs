@@ -0,0 +1,198 @@
+use crate::{fake_drawio_application, fake_xvfb, is_process_running, DrawioExporterCommand};
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicates::prelude::predicate::str::contains;
+use predicates::prelude::PredicateBooleanExt;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Display used by draw.io, as logged by the fake application
+fn logged_display(log_file: &Path) -> Result<String> {
+    let log = fs::read_to_string(log_file)?;
+    let display = log
+        .lines()
+        .next()
+        .and_then(|line| line.split("DISPLAY=").nth(1))
+        .unwrap_or_default();
+    Ok(display.to_string())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_on_virtual_display_without_display() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+    let xvfb_pid_file = drawio_exporter.current_dir.join("xvfb.pid");
+
+    drawio_exporter
+        .cmd
+        .env_remove("DISPLAY")
+        .env_remove("WAYLAND_DISPLAY")
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .env("FAKE_XVFB_PID", &xvfb_pid_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--xvfb")
+        .arg(fake_xvfb())
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains("+ start virtual display : :"));
+
+    // draw.io used the virtual display, which is stopped after the export
+    let display = logged_display(&log_file)?;
+    assert!(display.starts_with(':'));
+    assert!(!is_process_running(&fs::read_to_string(&xvfb_pid_file)?)?);
+    assert!(!Path::new(&format!("/tmp/.X11-unix/X{}", &display[1..])).exists());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_starts_virtual_display_before_running_draw_io() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    let output = drawio_exporter
+        .cmd
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--virtual-display")
+        .arg("xvfb")
+        .arg("--xvfb")
+        .arg(fake_xvfb())
+        .arg(&drawio_exporter.current_dir)
+        .output()?;
+
+    // The display isn't prepared while checking the options
+    let stdout = String::from_utf8(output.stdout)?;
+    assert!(output.status.success());
+    assert!(stdout.starts_with("+ export file : "), "{}", stdout);
+    assert!(stdout.contains("+ start virtual display : :"), "{}", stdout);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_on_current_display() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("DISPLAY", ":42")
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--xvfb")
+        .arg(fake_xvfb())
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains("+ start virtual display").not());
+
+    assert_eq!(logged_display(&log_file)?, ":42");
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_with_offscreen_rendering() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--virtual-display")
+        .arg("offscreen")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains("+ use offscreen rendering of draw.io desktop"));
+
+    assert!(fs::read_to_string(&log_file)?.contains("--ozone-platform=headless"));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_stops_virtual_display_on_error() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+    let xvfb_pid_file = drawio_exporter.current_dir.join("xvfb.pid");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .env("FAKE_DRAWIO_FAILURES", "1")
+        .env("FAKE_XVFB_PID", &xvfb_pid_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--virtual-display")
+        .arg("xvfb")
+        .arg("--xvfb")
+        .arg(fake_xvfb())
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains("Error: fake failure"));
+
+    assert!(!is_process_running(&fs::read_to_string(&xvfb_pid_file)?)?);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_stops_started_processes_on_interruption() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let hang_file = drawio_exporter.current_dir.join("hang.pid");
+    let xvfb_pid_file = drawio_exporter.current_dir.join("xvfb.pid");
+
+    let export = drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_HANG", &hang_file)
+        .env("FAKE_XVFB_PID", &xvfb_pid_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--virtual-display")
+        .arg("xvfb")
+        .arg("--xvfb")
+        .arg(fake_xvfb())
+        .arg("--keep-going")
+        .arg(&drawio_exporter.current_dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Interrupt the export once draw.io hangs
+    let started = Instant::now();
+    while !hang_file.exists() && started.elapsed() < Duration::from_secs(20) {
+        thread::sleep(Duration::from_millis(50));
+    }
+    thread::sleep(Duration::from_millis(200));
+    Command::new("kill")
+        .arg("-INT")
+        .arg(export.id().to_string())
+        .status()?;
+
+    let output = export.wait_with_output()?;
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("Error: export interrupted"), "{}", stderr);
+    assert!(started.elapsed() < Duration::from_secs(30));
+
+    assert!(!is_process_running(&fs::read_to_string(&hang_file)?)?);
+    assert!(!is_process_running(&fs::read_to_string(&xvfb_pid_file)?)?);
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use crate::{fake_drawio_application, DrawioExporterCommand};
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicates::prelude::predicate::str::contains;
+use std::fs;
+
+#[cfg(unix)]
+#[test]
+fn export_with_additional_drawio_arguments() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--drawio-arg=--svg-theme=dark")
+        .arg("--drawio-arg")
+        .arg("--layers=1")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+
+    // The additional arguments are set before the input
+    let log = fs::read_to_string(&log_file)?;
+    assert!(log
+        .lines()
+        .all(|line| line.contains("--svg-theme=dark --layers=1 ") && line.contains(".drawio")));
+    assert!(drawio_exporter
+        .current_dir
+        .join("types/export/nominal-Page-1.pdf")
+        .exists());
+
+    Ok(())
+}
+
+#[test]
+fn export_with_drawio_argument_set_by_drawio_exporter() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("--drawio-arg")
+        .arg("--format=png")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "draw.io argument '--format=png' is already set by drawio-exporter",
+        ));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_with_drawio_argument_not_set_for_the_export() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--drawio-arg=--page-range=1..2")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+
+    let log = fs::read_to_string(&log_file)?;
+    assert!(log.lines().all(|line| line.contains("--page-range=1..2 ")));
+
+    Ok(())
+}
+
+#[test]
+fn export_with_drawio_argument_set_for_the_export() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--single-pdf")
+        .arg("--page-range")
+        .arg("1..2")
+        .arg("--drawio-arg=--page-range=1..2")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "draw.io argument '--page-range=1..2' is already set by drawio-exporter",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn export_with_invalid_drawio_argument() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("--drawio-arg")
+        .arg("-l")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "draw.io argument '-l' must be formatted as --name or --name=value",
+        ));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_with_drawio_environment_and_working_directory() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let environment_file = drawio_exporter.current_dir.join("drawio.env");
+    let working_directory = fs::canonicalize(&drawio_exporter.current_dir)?.join("drawio");
+    fs::create_dir(&working_directory)?;
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_ENVIRONMENT", &environment_file)
+        .env("FAKE_REMOVED_VALUE", "removed")
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--drawio-env")
+        .arg("FAKE_VALUE=42")
+        .arg("--drawio-env-remove")
+        .arg("FAKE_REMOVED_VALUE")
+        .arg("--drawio-cwd")
+        .arg(&working_directory)
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+
+    let environment = fs::read_to_string(&environment_file)?;
+    assert!(environment.contains(&format!("cwd={}\n", working_directory.display())));
+    assert!(environment.contains("FAKE_VALUE=42\n"));
+    assert!(!environment.contains("FAKE_REMOVED_VALUE"));
+
+    // The relative paths of the files are resolved before changing the working directory
+    assert!(drawio_exporter
+        .current_dir
+        .join("types/export/nominal-Page-1.pdf")
+        .exists());
+    assert_eq!(fs::read_dir(&working_directory)?.count(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn export_with_missing_drawio_working_directory() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--drawio-cwd")
+        .arg("missing-folder")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "working directory 'missing-folder' of draw.io desktop don't exists",
+        ));
+
+    Ok(())
+}
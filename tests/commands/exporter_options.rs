@@ -1,4 +1,4 @@
-use crate::DrawioExporterCommand;
+use crate::{fake_drawio_application, DrawioExporterCommand};
 use anyhow::Result;
 use assert_cmd::prelude::*;
 use predicates::prelude::predicate::str::contains;
@@ -52,7 +52,7 @@ fn export_using_specific_path() -> Result<()> {
 
     drawio_exporter
         .cmd
-        .arg(&drawio_exporter.current_dir.join("types"))
+        .arg(drawio_exporter.current_dir.join("types"))
         .assert()
         .success()
         .stdout(contains(output));
@@ -79,3 +79,37 @@ fn export_using_unknown_path() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn export_with_search_index() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("text", true)?;
+    let search_index = drawio_exporter.current_dir.join("search-index.json");
+
+    let output = format!(
+        "+ export file : text/text.drawio
+- export page 1 : Page-1
+\\ generate pdf file
++ write search index : {}",
+        search_index.display()
+    );
+
+    drawio_exporter
+        .cmd
+        .arg("--application")
+        .arg(fake_drawio_application())
+        // The display is prepared on the first export, so it's kept out of the output
+        .arg("--virtual-display")
+        .arg("off")
+        .arg("--search-index")
+        .arg(&search_index)
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(output));
+
+    let search_index_content = std::fs::read_to_string(search_index)?;
+    assert!(search_index_content.contains(r#""export": "text/export/text-Page-1.pdf""#));
+    assert!(search_index_content.contains(r#""Web Server""#));
+
+    Ok(())
+}
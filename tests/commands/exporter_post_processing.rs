@@ -0,0 +1,146 @@
+use crate::{fake_drawio_application, DrawioExporterCommand};
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicates::prelude::predicate::str::contains;
+use std::fs;
+
+#[cfg(unix)]
+#[test]
+fn export_png_with_post_processing() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--format")
+        .arg("png")
+        .arg("--embed-diagram")
+        .arg("--post-process")
+        .arg("png:strip-metadata,optimize,scale=2x,webp")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains("\\ strip-metadata png file"))
+        .stdout(contains("\\ optimize png file"))
+        .stdout(contains("\\ generate png file at 2x scale"))
+        .stdout(contains("\\ convert png file to webp"));
+
+    // The variant is exported at a scale, then converted like the exported file
+    let log = fs::read_to_string(&log_file)?;
+    assert!(log.contains("--scale 2"));
+    let export_folder = drawio_exporter.current_dir.join("types/export");
+    let png = fs::read(export_folder.join("nominal-Page-1.png"))?;
+    assert!(!png.windows(6).any(|window| window == b"mxfile"));
+    assert!(export_folder.join("nominal-Page-1@2x.png").exists());
+    for webp in ["nominal-Page-1.webp", "nominal-Page-1@2x.webp"] {
+        assert!(fs::read(export_folder.join(webp))?.starts_with(b"RIFF"));
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_png_in_batch_with_scale_post_processing() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--format")
+        .arg("png")
+        .arg("--batch")
+        .arg("--post-process")
+        .arg("png:scale=2x")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains("\\ generate png files of all pages at 2x scale"));
+
+    // The variants of all the pages are exported with a single execution too
+    let log = fs::read_to_string(&log_file)?;
+    assert_eq!(log.lines().count(), 2);
+    assert!(log.lines().all(|line| line.contains("--all-pages")));
+    assert!(log.lines().nth(1).unwrap().contains("--scale 2"));
+    let export_folder = drawio_exporter.current_dir.join("types/export");
+    assert!(export_folder.join("nominal-Page-1@2x.png").exists());
+    assert!(export_folder.join("nominal-Page-2@2x.png").exists());
+    assert!(!export_folder.join(".batch-nominal").exists());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_svg_with_post_processing() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--format")
+        .arg("svg")
+        .arg("--embed-diagram")
+        .arg("--post-process")
+        .arg("svg:strip-metadata")
+        .arg("--post-process")
+        .arg("svg:minify")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+
+    let svg = fs::read_to_string(
+        drawio_exporter
+            .current_dir
+            .join("types/export/nominal-Page-1.svg"),
+    )?;
+    assert!(svg.starts_with("<svg"));
+    assert!(!svg.contains("content="));
+    assert!(!svg.contains("<!--"));
+    // Only the whitespaces of the elements preserving them are kept
+    assert_eq!(svg.matches('\n').count(), 1);
+    assert!(svg.contains("<tspan>fake</tspan>\n    <tspan>label</tspan>"));
+
+    Ok(())
+}
+
+#[test]
+fn export_with_post_processing_step_not_supporting_format() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("--post-process")
+        .arg("svg:webp")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "post-processing step webp doesn't support svg format (only png)",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn export_with_unknown_post_processing_step() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("--post-process")
+        .arg("png:avif")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains("unknown post-processing step 'avif'"));
+
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use crate::{fake_drawio_application, DrawioExporterCommand};
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use drawio_exporter::core::drawio::export_backend::{ExportCall, FakeBackend};
+use drawio_exporter::core::explorer::ExploreOptions;
+use drawio_exporter::core::post_processing::PostProcessing;
+use drawio_exporter::ops::exporter::{exporter, ExporterOptions, PageRange};
+use predicates::prelude::predicate::str::contains;
+use std::collections::BTreeMap;
+use std::fs;
+use std::time::Duration;
+
+#[cfg(unix)]
+#[test]
+fn export_all_pages_into_single_pdf() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    let output = "+ export file : types/nominal.drawio
+- export pages 1 to 2
+\\ generate pdf file";
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--single-pdf")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(output));
+
+    let log = fs::read_to_string(&log_file)?;
+    assert_eq!(log.lines().count(), 1);
+    assert!(log.contains("--all-pages"));
+
+    let export_folder = drawio_exporter.current_dir.join("types/export");
+    assert!(export_folder.join("nominal.pdf").exists());
+    assert!(!export_folder.join("nominal-Page-1.pdf").exists());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_page_range_into_single_pdf() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--single-pdf")
+        .arg("--page-range")
+        .arg("2..5")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains("- export pages 2 to 2"));
+
+    // The range is limited to the pages of the file
+    let log = fs::read_to_string(&log_file)?;
+    assert!(log.contains("--page-range 2..2"));
+    assert!(!log.contains("--all-pages"));
+    assert!(drawio_exporter
+        .current_dir
+        .join("types/export/nominal.pdf")
+        .exists());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_page_range_after_the_last_page() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--single-pdf")
+        .arg("--page-range")
+        .arg("3..4")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(
+            "warn: no page exported, due to page range 3..4 of a file with 2 page(s)",
+        ));
+
+    assert!(!drawio_exporter
+        .current_dir
+        .join("types/export/nominal.pdf")
+        .exists());
+
+    Ok(())
+}
+
+#[test]
+fn export_single_pdf_in_another_format() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("--single-pdf")
+        .arg("--format")
+        .arg("png")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "Error: single pdf export needs pdf format, not png format",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn export_with_invalid_page_range() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("--single-pdf")
+        .arg("--page-range")
+        .arg("3..1")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "page range '3..1' must be formatted as from..to, with pages numbered from 1",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn export_page_range_into_single_pdf_with_fake_backend() -> Result<()> {
+    let drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let backend = FakeBackend::new();
+    let path = drawio_exporter.current_dir.to_str().unwrap();
+    let folder = "export".to_string();
+    let format = "pdf".to_string();
+    let border = "0".to_string();
+    let quality = "90".to_string();
+
+    exporter(ExporterOptions {
+        backend: &backend,
+        retries: 0,
+        retry_delay: Duration::ZERO,
+        folder: &folder,
+        on_filesystem_changes: false,
+        on_git_changes_since_reference: None,
+        remove_page_suffix: false,
+        explore_options: &ExploreOptions::default(),
+        path,
+        format: &format,
+        border: &border,
+        scale: None,
+        enable_plugins: false,
+        width: None,
+        height: None,
+        crop: false,
+        transparent: false,
+        quality: &quality,
+        uncompressed: false,
+        embed_svg_images: false,
+        embed_diagram: false,
+        batch: false,
+        single_pdf: true,
+        page_range: Some(PageRange { from: 1, to: 2 }),
+        post_processing: &PostProcessing::new(),
+        search_index: None,
+        text_alternative: false,
+        variables: &BTreeMap::new(),
+    })?;
+
+    let export_folder = drawio_exporter.current_dir.join("types/export");
+    assert_eq!(
+        backend.calls(),
+        vec![ExportCall {
+            input: drawio_exporter
+                .current_dir
+                .join("types/nominal.drawio")
+                .to_str()
+                .unwrap()
+                .to_string(),
+            output: Some(
+                export_folder
+                    .join("nominal.pdf")
+                    .to_str()
+                    .unwrap()
+                    .to_string()
+            ),
+            format: "pdf".to_string(),
+            page_index: None,
+            page_range: Some("1..2".to_string()),
+            all_pages: false,
+        }]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use crate::{fake_drawio_application, is_process_running, DrawioExporterCommand};
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicates::prelude::predicate::str::contains;
+use std::fs;
+use std::time::{Duration, Instant};
+
+#[cfg(unix)]
+#[test]
+fn export_kills_draw_io_on_timeout() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let hang_file = drawio_exporter.current_dir.join("hang.pid");
+
+    let started = Instant::now();
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_HANG", &hang_file)
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--timeout")
+        .arg("1")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains("can't process"))
+        .stderr(contains("types/nominal.drawio"))
+        .stderr(contains("can't export page 1 : Page-1"))
+        .stderr(contains(
+            "draw.io desktop didn't finish within 1s, its processes were killed",
+        ));
+    assert!(started.elapsed() < Duration::from_secs(30));
+
+    // The child process of draw.io is killed too
+    assert!(!is_process_running(&fs::read_to_string(&hang_file)?)?);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_retries_after_transient_failures() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .env("FAKE_DRAWIO_FAILURES", "2")
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--retries")
+        .arg("2")
+        .arg("--retry-delay")
+        .arg("0")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(
+            "warn: export failed, retry 1 of 2 in 0s: fail to export using draw.io desktop",
+        ))
+        .stdout(contains("warn: export failed, retry 2 of 2 in 0s"));
+
+    // 2 failures, then 1 execution for each page
+    assert_eq!(fs::read_to_string(&log_file)?.lines().count(), 4);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_fails_when_retries_are_exhausted() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .env("FAKE_DRAWIO_FAILURES", "5")
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--retries")
+        .arg("1")
+        .arg("--retry-delay")
+        .arg("0")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains("can't export page 1 : Page-1"))
+        .stderr(contains("Error: fake failure"));
+
+    assert_eq!(fs::read_to_string(&log_file)?.lines().count(), 2);
+
+    Ok(())
+}
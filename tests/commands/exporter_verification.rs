@@ -0,0 +1,97 @@
+use crate::{fake_drawio_application, DrawioExporterCommand};
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicates::prelude::predicate::str::contains;
+use std::fs;
+
+#[cfg(unix)]
+#[test]
+fn export_fails_on_empty_exported_file() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .env("FAKE_DRAWIO_EMPTY", "1")
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--format")
+        .arg("png")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains("can't export page 1 : Page-1"))
+        .stderr(contains("exported png file"))
+        .stderr(contains("nominal-Page-1.png is empty"));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_retries_on_empty_exported_file() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .env("FAKE_DRAWIO_EMPTY", "1")
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--format")
+        .arg("png")
+        .arg("--retries")
+        .arg("1")
+        .arg("--retry-delay")
+        .arg("0")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(
+            "warn: export failed, retry 1 of 1 in 0s: exported png file",
+        ));
+
+    assert!(
+        fs::metadata(
+            drawio_exporter
+                .current_dir
+                .join("types/export/nominal-Page-1.png")
+        )?
+        .len()
+            > 0
+    );
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn export_in_batch_fails_on_empty_exported_file() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let log_file = drawio_exporter.current_dir.join("drawio.log");
+
+    drawio_exporter
+        .cmd
+        .env("FAKE_DRAWIO_LOG", &log_file)
+        .env("FAKE_DRAWIO_EMPTY", "1")
+        .arg("--application")
+        .arg(fake_drawio_application())
+        .arg("--batch")
+        .arg("--format")
+        .arg("svg")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains("can't export all pages"))
+        .stderr(contains("nominal-1.svg is empty"));
+
+    // The batch folder is removed, even on failure
+    assert!(!drawio_exporter
+        .current_dir
+        .join("types/export/.batch-nominal")
+        .exists());
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use crate::DrawioExporterCommand;
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicates::prelude::predicate::str::contains;
+use std::fs;
+
+#[test]
+fn fmt_file() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("fmt", true)?;
+
+    let content = r#"<mxfile pages="1" type="device">
+  <diagram id="page-1" name="Page-1">
+    <mxGraphModel grid="1" gridSize="10" page="1">
+      <root>
+        <mxCell id="0"/>
+        <mxCell id="1" parent="0"/>
+        <mxCell id="2" parent="1" style="ellipse;" value="Start" vertex="1">
+          <mxGeometry as="geometry" height="80" width="80" x="20" y="40"/>
+        </mxCell>
+      </root>
+    </mxGraphModel>
+  </diagram>
+</mxfile>
+"#;
+
+    drawio_exporter
+        .cmd
+        .arg("fmt")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout("+ format file : fmt/noisy.drawio\n");
+
+    assert_eq!(
+        fs::read_to_string(drawio_exporter.current_dir.join("fmt/noisy.drawio"))?,
+        content
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fmt_check() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("fmt", true)?;
+    let path = drawio_exporter.current_dir.join("fmt/noisy.drawio");
+    let content = fs::read_to_string(&path)?;
+
+    drawio_exporter
+        .cmd
+        .arg("fmt")
+        .arg("--check")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .code(1)
+        .stdout("- not formatted file : fmt/noisy.drawio\n")
+        .stderr(contains("found 1 not formatted file(s)"));
+    assert_eq!(fs::read_to_string(&path)?, content);
+
+    drawio_exporter.new_cmd()?;
+    drawio_exporter
+        .cmd
+        .arg("fmt")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+
+    drawio_exporter.new_cmd()?;
+    drawio_exporter
+        .cmd
+        .arg("fmt")
+        .arg("--check")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout("");
+
+    Ok(())
+}
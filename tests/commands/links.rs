@@ -0,0 +1,272 @@
+use crate::{mock_http_server, DrawioExporterCommand};
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicates::prelude::predicate::str::contains;
+
+#[test]
+fn links_from_file() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("links", true)?;
+
+    let output = "+ file : links/links.drawio
+- page 1 : Page-1
+page link 'Text Page Link 1' to data:page/id,ywT4ct3H2L5pf33UiNoI
+page link 'Shape Page Link 1' to data:page/id,ywT4ct3H2L5pf33UiNoI
+external link 'Text Link 1' to https://github.com/rlespinasse/drawio-exporter
+external link 'Shape Link 1' to https://github.com/rlespinasse/drawio-exporter
+- page 2 : Page-2
+page link 'Text Page Link 1' to data:page/id,Apcybv1_2TdogQw9BSl8
+page link 'Shape Page Link 1' to data:page/id,Apcybv1_2TdogQw9BSl8
+external link 'Link on multiple Lines' to https://github.com/rlespinasse/drawio-exporter
+- page 3 : empty-link
+external link 'Empty Link' to 
+- page 4 : empty-text
+external link '' to https://github.com/rlespinasse/drawio-exporter";
+
+    drawio_exporter
+        .cmd
+        .arg("links")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(output));
+
+    Ok(())
+}
+
+#[test]
+fn links_check_from_file() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("lint", true)?;
+    let (server_address, _) = mock_http_server(|path| match path {
+        "/ok" => ("200 OK", String::new()),
+        _ => ("404 Not Found", String::new()),
+    })?;
+    drawio_exporter.new_file_in_folder("lint/docs", "existing.md", "")?;
+    drawio_exporter.new_file_in_folder(
+        "lint",
+        "check.drawio",
+        &links_drawio_file(&[
+            ("Existing Url", &format!("http://{}/ok", server_address)),
+            ("Dead Url", &format!("http://{}/not-found", server_address)),
+            ("Existing File", "docs/existing.md#title"),
+            ("Dead File", "docs/missing.md"),
+            ("Existing Anchor", "#check-page"),
+            ("Dead Anchor", "#missing-anchor"),
+            ("Existing Page", "data:page/id,check-page"),
+            ("Other Link", "mailto:someone@example.com"),
+        ]),
+    )?;
+
+    let output = format!(
+        "lint/check.drawio: page 1 'Page-1': dead link 'Dead Url' to http://{}/not-found (status code 404)
+lint/check.drawio: page 1 'Page-1': dead link 'Dead File' to docs/missing.md (file not found)
+lint/check.drawio: page 1 'Page-1': dead link 'Dead Anchor' to #missing-anchor (unknown anchor)
+lint/lint.drawio: page 1 'Page': dead link 'Broken Page Link' to data:page/id,unknown-page (unknown page)
+",
+        server_address
+    );
+
+    drawio_exporter
+        .cmd
+        .arg("links")
+        .arg("check")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stdout(output)
+        .stderr(contains("Error: found 4 dead link(s)"));
+
+    Ok(())
+}
+
+#[test]
+fn links_check_rooted_links() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("lint", true)?;
+    drawio_exporter.new_file_in_folder("docs", "guide.md", "")?;
+    drawio_exporter.new_file_in_folder(
+        "lint",
+        "check.drawio",
+        &links_drawio_file(&[
+            ("Existing Rooted File", "/docs/guide.md#install"),
+            ("Dead Rooted File", "/docs/missing.md"),
+            ("Network Path", "//example.com/docs/guide.md"),
+        ]),
+    )?;
+
+    let output = "lint/check.drawio: page 1 'Page-1': dead link 'Dead Rooted File' to /docs/missing.md (file not found)
+lint/lint.drawio: page 1 'Page': dead link 'Broken Page Link' to data:page/id,unknown-page (unknown page)
+";
+
+    drawio_exporter
+        .cmd
+        .arg("links")
+        .arg("check")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stdout(output)
+        .stderr(contains("Error: found 2 dead link(s)"));
+
+    Ok(())
+}
+
+#[test]
+fn links_check_without_http() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("links", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("links")
+        .arg("check")
+        .arg("--no-http")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(""); // No Output
+
+    Ok(())
+}
+
+fn links_drawio_file(links: &[(&str, &str)]) -> String {
+    let cells: String = links
+        .iter()
+        .enumerate()
+        .map(|(index, (label, link))| {
+            format!(
+                r#"<UserObject label="{}" link="{}" id="check-{}"><mxCell style="rounded=0;" parent="1" vertex="1"><mxGeometry width="120" height="60" as="geometry"/></mxCell></UserObject>"#,
+                label, link, index
+            )
+        })
+        .collect();
+    format!(
+        r#"<mxfile pages="1"><diagram id="check-page" name="Page-1"><mxGraphModel><root><mxCell id="0"/><mxCell id="1" parent="0"/>{}</root></mxGraphModel></diagram></mxfile>"#,
+        cells
+    )
+}
+
+#[test]
+fn links_from_html_labels() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("html_links", true)?;
+
+    let output = "+ file : html_links/html-links.drawio
+- page 1 : Page-1
+external link 'First' to https://example.com/first
+external link 'Second' to https://example.com/second
+external link 'Single Quoted' to https://example.com/single-quoted
+external link 'Nested formatting tags' to https://example.com/search?q=drawio&page=2
+";
+
+    drawio_exporter
+        .cmd
+        .arg("links")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(output);
+
+    Ok(())
+}
+
+#[test]
+fn links_from_every_source() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("link_sources", true)?;
+
+    let output = "+ file : link_sources/link-sources.drawio
+- page 1 : Page-1
+external link 'Cell Link' to https://example.com/cell
+external link 'Object Link' to https://example.com/object
+external link 'Label Anchor' to https://example.com/label
+external link 'Tooltip Anchor' to https://example.com/tooltip
+placeholder link 'Placeholder Link' to https://example.com/%missing%
+external link 'Not a Placeholder' to https://example.com/%version%
+action link 'Action Link' to data:action/json,{\"actions\":[{\"toggle\":{\"cells\":[\"cell-link\"]}}]}
+page link 'Page Link' to data:page/id,page-2
+- page 2 : Page-2
+";
+
+    drawio_exporter
+        .cmd
+        .arg("links")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(output);
+
+    Ok(())
+}
+
+#[test]
+fn links_check_with_every_source() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("link_sources", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("links")
+        .arg("check")
+        .arg("--no-http")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn links_with_placeholders() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("placeholders", true)?;
+
+    let output = "+ file : placeholders/placeholders.drawio
+- page 1 : Overview
+external link 'Release 1.0 of drawio-exporter' to https://example.com/drawio-exporter/releases/1.0
+placeholder link 'Built with 2.0' to https://example.com/2.0/%unknown%
+external link 'Raw %page%' to https://example.com/%page%
+- page 2 : Details
+";
+
+    drawio_exporter
+        .cmd
+        .env("DRAWIO_EXPORTER_VAR_release", "from-env")
+        .arg("links")
+        .arg("--var")
+        .arg("release=2.0")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(output);
+
+    Ok(())
+}
+
+#[test]
+fn links_with_placeholders_from_environment() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("placeholders", true)?;
+
+    drawio_exporter
+        .cmd
+        .env("DRAWIO_EXPORTER_VAR_release", "from-env")
+        .arg("links")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(
+            "placeholder link 'Built with from-env' to https://example.com/from-env/%unknown%",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn links_with_malformed_variable() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("placeholders", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("links")
+        .arg("--var")
+        .arg("release")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains("'release' must be formatted as name=value"));
+
+    Ok(())
+}
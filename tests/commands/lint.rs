@@ -0,0 +1,167 @@
+use crate::DrawioExporterCommand;
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicates::prelude::predicate::str::contains;
+
+#[test]
+fn lint_file_with_links_problems() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("links", true)?;
+
+    let output = "links/links.drawio: page 3 'empty-link': link 'Empty Link' has no url [link-missing-url]
+links/links.drawio: page 4 'empty-text': link to https://github.com/rlespinasse/drawio-exporter has no label [link-missing-label]";
+
+    drawio_exporter
+        .cmd
+        .arg("lint")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stdout(contains(output))
+        .stderr(contains("Error: lint found 2 problem(s)"));
+
+    Ok(())
+}
+
+#[test]
+fn lint_file_with_pages_problems() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("lint", true)?;
+
+    let output = "lint/lint.drawio: page 1 'Page': link 'Broken Page Link' targets an unknown page unknown-page [broken-page-link]
+lint/lint.drawio: page 2 'Page': page name is already used by another page [duplicate-page-name]
+lint/lint.drawio: page 3 'Empty': page is empty [empty-page]
+lint/lint.drawio: page 4 'Unsafe: Page/Name': page name can't be safely used as a filename [unsafe-page-name]";
+
+    drawio_exporter
+        .cmd
+        .arg("lint")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stdout(contains(output))
+        .stderr(contains("Error: lint found 4 problem(s)"));
+
+    Ok(())
+}
+
+#[test]
+fn lint_valid_files() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("tree", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("lint")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(""); // No Output
+
+    Ok(())
+}
+
+#[test]
+fn lint_only_selected_rules() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("lint", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("lint")
+        .arg("--rule")
+        .arg("empty-page")
+        .arg("--rule")
+        .arg("unsafe-page-name")
+        .arg("--skip-rule")
+        .arg("unsafe-page-name")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stdout("lint/lint.drawio: page 3 'Empty': page is empty [empty-page]\n");
+
+    Ok(())
+}
+
+#[test]
+fn lint_storage_policy() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("single_page", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("lint")
+        .arg("--storage")
+        .arg("uncompressed")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stdout(
+            "single_page/single-page.drawio: page 1 'Page-1': page must be uncompressed [storage]\n",
+        );
+
+    drawio_exporter.new_cmd()?;
+    drawio_exporter
+        .cmd
+        .arg("lint")
+        .arg("--storage")
+        .arg("compressed")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn lint_storage_policy_with_mixed_storage() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("mixed_storage", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("lint")
+        .arg("--storage")
+        .arg("uncompressed")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stdout(
+            "mixed_storage/mixed-storage.drawio: page 1 'Compressed': page must be uncompressed [storage]\n",
+        );
+
+    drawio_exporter.new_cmd()?;
+    drawio_exporter
+        .cmd
+        .arg("lint")
+        .arg("--storage")
+        .arg("compressed")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stdout("mixed_storage/mixed-storage.drawio: page 2 'Plain': page must be compressed [storage]\n");
+
+    Ok(())
+}
+
+#[test]
+fn lint_as_json() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("lint", true)?;
+
+    let output = r#"[
+  {
+    "file": "lint/lint.drawio",
+    "page": 3,
+    "page_name": "Empty",
+    "rule": "empty-page",
+    "message": "page is empty"
+  }
+]"#;
+
+    drawio_exporter
+        .cmd
+        .arg("lint")
+        .arg("--json")
+        .arg("--rule")
+        .arg("empty-page")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stdout(contains(output));
+
+    Ok(())
+}
@@ -0,0 +1,348 @@
+use crate::DrawioExporterCommand;
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use filetime::FileTime;
+use git2::Repository;
+use predicates::prelude::predicate::str::contains;
+use predicates::prelude::PredicateBooleanExt;
+
+#[test]
+fn list_files_from_a_folders_tree() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("tree", true)?;
+
+    let output = "FILE                               PAGE  NAME    ID                    CELLS  LINKS  EXPORT
+tree/file1.drawio                  1     Page-1  KgwwwuPXLq_iXDXmIebJ  4      2      missing
+tree/file1.drawio                  2     Page 2  WKCg2NcY1VQb7qcMgWaw  4      2      missing
+tree/folder1/file2.1.drawio        1     Page-1  KgwwwuPXLq_iXDXmIebJ  4      2      missing
+tree/folder1/file2.1.drawio        2     Page 2  WKCg2NcY1VQb7qcMgWaw  4      2      missing
+tree/folder1/file2.2.drawio        1     Page-1  KgwwwuPXLq_iXDXmIebJ  4      2      missing
+tree/folder1/file2.2.drawio        2     Page 2  WKCg2NcY1VQb7qcMgWaw  4      2      missing
+tree/folder2/folder3/file3.drawio  1     Page-1  KgwwwuPXLq_iXDXmIebJ  4      2      missing
+tree/folder2/folder3/file3.drawio  2     Page 2  WKCg2NcY1VQb7qcMgWaw  4      2      missing";
+
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(output));
+
+    Ok(())
+}
+
+#[test]
+fn list_nothing() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("empty_folder", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(""); // No Output
+
+    Ok(())
+}
+
+#[test]
+fn list_as_json_with_export_status() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("types", true)?;
+    let export_folder =
+        drawio_exporter.new_file_in_folder("types/export", "nominal-Page-1.png", "")?;
+    drawio_exporter.new_file_in_folder("types/export", "nominal-Page-2.png", "")?;
+
+    // The second page export is older than the drawio file
+    let drawio_file = drawio_exporter.current_dir.join("types/nominal.drawio");
+    let drawio_file_mtime = FileTime::from_last_modification_time(&drawio_file.metadata()?);
+    filetime::set_file_mtime(
+        export_folder.join("nominal-Page-2.png"),
+        FileTime::from_unix_time(drawio_file_mtime.unix_seconds() - 7200, 0),
+    )?;
+
+    let output = r#"[
+  {
+    "file": "types/nominal.drawio",
+    "page": 1,
+    "name": "Page-1",
+    "id": "KgwwwuPXLq_iXDXmIebJ",
+    "cells": 4,
+    "links": 0,
+    "export": "exported"
+  },
+  {
+    "file": "types/nominal.drawio",
+    "page": 2,
+    "name": "Page 2",
+    "id": "WKCg2NcY1VQb7qcMgWaw",
+    "cells": 4,
+    "links": 0,
+    "export": "outdated"
+  }
+]"#;
+
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg("--json")
+        .arg("--format")
+        .arg("png")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(output));
+
+    Ok(())
+}
+
+#[test]
+fn list_files_with_embedded_diagrams() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("embedded", true)?;
+    drawio_exporter.new_file_in_folder("embedded/export", "embedded-svg.pdf", "")?;
+
+    let output = "FILE                                     PAGE  NAME            ID                   CELLS  LINKS  EXPORT
+embedded/embedded-compressed.drawio.png  1     png-compressed  png-compressed-page  3      1      missing
+embedded/embedded-text.drawio.png        1     png-text        png-text-page        3      1      missing
+embedded/embedded.drawio.svg             1     svg             svg-page             3      1      exported
+";
+
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(output);
+
+    Ok(())
+}
+
+#[test]
+fn list_files_with_configured_extensions() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("embedded", true)?;
+
+    let output = "FILE                          PAGE  NAME  ID        CELLS  LINKS  EXPORT
+embedded/embedded.drawio.svg  1     svg   svg-page  3      1      missing
+embedded/raw.xml              1     xml   xml-page  3      1      missing
+";
+
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg("--extension")
+        .arg("drawio.svg")
+        .arg("--extension")
+        .arg(".xml")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(output);
+
+    Ok(())
+}
+
+#[test]
+fn list_image_without_embedded_diagram() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("embedded", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg("--extension")
+        .arg("png")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains("no diagram embedded in png"));
+
+    Ok(())
+}
+
+#[test]
+fn list_files_with_ignore_file() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("explore", true)?;
+
+    let output = "FILE                            PAGE  NAME    ID       CELLS  LINKS  EXPORT
+explore/deep/a/b/deep.drawio    1     Page-1  deep     2      0      missing
+explore/diagram.drawio          1     Page-1  diagram  2      0      missing
+explore/vendor/lib/copy.drawio  1     Page-1  copy     2      0      missing
+";
+
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(output);
+
+    Ok(())
+}
+
+#[test]
+fn list_files_with_include_and_exclude_globs() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("explore", true)?;
+
+    let output = "FILE                          PAGE  NAME    ID       CELLS  LINKS  EXPORT
+explore/deep/a/b/deep.drawio  1     Page-1  deep     2      0      missing
+explore/diagram.drawio        1     Page-1  diagram  2      0      missing
+";
+
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg("--include")
+        .arg("explore/**")
+        .arg("--exclude")
+        .arg("**/vendor/**")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(output);
+
+    Ok(())
+}
+
+#[test]
+fn list_files_with_hidden_files_and_max_depth() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("explore", true)?;
+
+    let output = "FILE                           PAGE  NAME    ID       CELLS  LINKS  EXPORT
+explore/.hidden/hidden.drawio  1     Page-1  hidden   2      0      missing
+explore/diagram.drawio         1     Page-1  diagram  2      0      missing
+";
+
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg("--hidden")
+        .arg("--max-depth")
+        .arg("3")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(output);
+
+    Ok(())
+}
+
+#[test]
+fn list_files_ignored_by_git() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("explore", false)?;
+    Repository::init(&drawio_exporter.current_dir)?;
+    drawio_exporter.new_file(".gitignore", "vendor/")?;
+    let explore_folder = drawio_exporter.current_dir.join("explore");
+
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg(&explore_folder)
+        .assert()
+        .success()
+        .stdout(contains("vendor/lib/copy.drawio").not());
+
+    drawio_exporter.new_cmd()?;
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg("--no-gitignore")
+        .arg(&explore_folder)
+        .assert()
+        .success()
+        .stdout(contains("vendor/lib/copy.drawio"));
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn list_files_following_symlinks() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("explore", true)?;
+    let explore_folder = drawio_exporter.current_dir.join("explore");
+    std::os::unix::fs::symlink(explore_folder.join("deep"), explore_folder.join("link"))?;
+
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg(&explore_folder)
+        .assert()
+        .success()
+        .stdout(contains("link/a/b/deep.drawio").not());
+
+    drawio_exporter.new_cmd()?;
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg("--follow-symlinks")
+        .arg(&explore_folder)
+        .assert()
+        .success()
+        .stdout(contains("link/a/b/deep.drawio"));
+
+    Ok(())
+}
+
+#[test]
+fn list_stops_on_corrupted_file() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("corrupted", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(contains("broken.drawio"));
+
+    Ok(())
+}
+
+#[test]
+fn list_keeps_going_on_corrupted_file() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("corrupted", true)?;
+
+    let output = "FILE                   PAGE  NAME    ID       CELLS  LINKS  EXPORT
+corrupted/good.drawio  1     Page-1  diagram  2      0      missing
+";
+
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg("--keep-going")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .code(3)
+        .stdout(output)
+        .stderr(contains(
+            "+ failed files :\n- corrupted/broken.drawio : can parse xml",
+        ))
+        .stderr(contains("Error: 1 of 2 file(s) failed"));
+
+    Ok(())
+}
+
+#[test]
+fn list_keeps_going_on_corrupted_page() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("corrupted_page", true)?;
+
+    let output = "FILE                        PAGE  NAME    ID       CELLS  LINKS  EXPORT
+corrupted_page/good.drawio  1     Page-1  diagram  2      0      missing
+";
+
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg("--keep-going")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .code(3)
+        .stdout(output)
+        .stderr(contains(
+            "- corrupted_page/damaged.drawio : can parse page Page-1: can uncompress xml",
+        ))
+        .stderr(contains("Error: 1 of 2 file(s) failed"));
+
+    Ok(())
+}
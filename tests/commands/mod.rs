@@ -1,6 +1,25 @@
+mod book;
+mod clean;
+mod diff;
 mod exporter;
+mod exporter_backend;
+mod exporter_batch;
+mod exporter_discovery;
+mod exporter_display;
+mod exporter_drawio_arguments;
 mod exporter_links;
 mod exporter_option_git_ref;
 mod exporter_option_on_changes;
 mod exporter_options;
+mod exporter_post_processing;
+mod exporter_single_pdf;
+mod exporter_timeouts;
 mod exporter_types;
+mod exporter_verification;
+mod fmt;
+mod links;
+mod lint;
+mod list;
+mod normalize;
+mod split;
+mod text;
@@ -0,0 +1,113 @@
+use crate::DrawioExporterCommand;
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use std::fs;
+
+#[test]
+fn normalize_compressed_file() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("normalize", true)?;
+
+    let content = r#"<mxfile host="Electron" compressed="false" version="24.0.0">
+  <diagram id="page-1" name="Page-1">
+    <mxGraphModel dx="800" dy="600">
+      <root>
+        <mxCell id="0"/>
+        <mxCell id="1" parent="0"/>
+        <mxCell id="2" value="Hello &amp; welcome" style="rounded=1;" vertex="1" parent="1">
+          <mxGeometry x="10" y="20" width="120" height="60" as="geometry"/>
+        </mxCell>
+      </root>
+    </mxGraphModel>
+  </diagram>
+</mxfile>
+"#;
+
+    drawio_exporter
+        .cmd
+        .arg("normalize")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout("+ normalize file : normalize/compressed.drawio\n");
+
+    assert_eq!(
+        fs::read_to_string(
+            drawio_exporter
+                .current_dir
+                .join("normalize/compressed.drawio")
+        )?,
+        content
+    );
+
+    Ok(())
+}
+
+#[test]
+fn normalize_round_trip() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("normalize", true)?;
+    let path = drawio_exporter
+        .current_dir
+        .join("normalize/compressed.drawio");
+
+    drawio_exporter
+        .cmd
+        .arg("normalize")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+    let uncompressed = fs::read_to_string(&path)?;
+
+    drawio_exporter.new_cmd()?;
+    drawio_exporter
+        .cmd
+        .arg("normalize")
+        .arg("--compressed")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout("+ normalize file : normalize/compressed.drawio\n");
+    assert!(!fs::read_to_string(&path)?.contains("<mxGraphModel"));
+
+    drawio_exporter.new_cmd()?;
+    drawio_exporter
+        .cmd
+        .arg("normalize")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success();
+    assert_eq!(fs::read_to_string(&path)?, uncompressed);
+
+    Ok(())
+}
+
+#[test]
+fn normalize_keeps_diagrams_content() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("links", true)?;
+
+    let links = drawio_exporter
+        .cmd
+        .arg("links")
+        .arg(&drawio_exporter.current_dir)
+        .output()?
+        .stdout;
+
+    drawio_exporter.new_cmd()?;
+    drawio_exporter
+        .cmd
+        .arg("normalize")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout("+ normalize file : links/links.drawio\n");
+
+    drawio_exporter.new_cmd()?;
+    drawio_exporter
+        .cmd
+        .arg("links")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(String::from_utf8(links)?);
+
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use crate::DrawioExporterCommand;
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicates::prelude::predicate::str::contains;
+use std::fs;
+
+#[test]
+fn split_multi_pages_file() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("split", true)?;
+
+    let output = "+ split file : split/book.drawio
+- write page Intro : split/book-Intro.drawio
+warn: page link 'Read the details' targets page details which is now in another file
+- write page Details : split/book-Details.drawio
+";
+
+    drawio_exporter
+        .cmd
+        .arg("split")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(output);
+
+    drawio_exporter.new_cmd()?;
+    drawio_exporter
+        .cmd
+        .arg("list")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(
+            "split/book-Details.drawio  1     Details  details  3      0      missing",
+        ))
+        .stdout(contains(
+            "split/book-Intro.drawio    1     Intro    intro    3      1      missing",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn split_into_folder() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("split", true)?;
+
+    drawio_exporter
+        .cmd
+        .arg("split")
+        .arg("--output")
+        .arg("pages")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(
+            "- write page Intro : split/pages/book-Intro.drawio",
+        ));
+
+    assert!(drawio_exporter
+        .current_dir
+        .join("split/pages/book-Details.drawio")
+        .exists());
+
+    Ok(())
+}
+
+#[test]
+fn split_page_with_unsafe_name() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("split", true)?;
+    let book = drawio_exporter.current_dir.join("split/book.drawio");
+    fs::write(
+        &book,
+        fs::read_to_string(&book)?.replace("name=\"Details\"", "name=\"../Details\""),
+    )?;
+
+    drawio_exporter
+        .cmd
+        .arg("split")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "can't split page '../Details', its name can't be safely used as a filename",
+        ));
+
+    // No page is written, even the ones with a safe name
+    assert!(!drawio_exporter
+        .current_dir
+        .join("split/book-Intro.drawio")
+        .exists());
+    assert!(!drawio_exporter.current_dir.join("Details.drawio").exists());
+
+    Ok(())
+}
+
+#[test]
+fn split_pages_with_same_name() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("split", true)?;
+    let book = drawio_exporter.current_dir.join("split/book.drawio");
+    fs::write(
+        &book,
+        fs::read_to_string(&book)?.replace("name=\"Details\"", "name=\"Intro\""),
+    )?;
+
+    drawio_exporter
+        .cmd
+        .arg("split")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .failure()
+        .stderr(contains(
+            "can't split pages 'Intro' and 'Intro', they would both be written in split/book-Intro.drawio",
+        ));
+
+    assert!(!drawio_exporter
+        .current_dir
+        .join("split/book-Intro.drawio")
+        .exists());
+
+    Ok(())
+}
+
+#[test]
+fn merge_files_with_same_page_ids() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("merge", true)?;
+    let merge_folder = drawio_exporter.current_dir.join("merge");
+    let merged_path = drawio_exporter.current_dir.join("merged.drawio");
+
+    drawio_exporter
+        .cmd
+        .arg("merge")
+        .arg("--output")
+        .arg(&merged_path)
+        .arg(merge_folder.join("a.drawio"))
+        .arg(merge_folder.join("b.drawio"))
+        .assert()
+        .success()
+        .stdout(contains("b.drawio already used id page-1, now page-1-2"));
+
+    let output = "+ file : merged.drawio
+- page 1 : A
+- page 2 : B1
+- page 3 : B2
+page link 'Back' to data:page/id,page-1-2
+page link 'First page' to data:page/id,page-1-2
+";
+
+    drawio_exporter.new_cmd()?;
+    drawio_exporter
+        .cmd
+        .arg("links")
+        .arg("--exclude")
+        .arg("merge/*")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(output);
+
+    Ok(())
+}
+
+#[test]
+fn merge_into_image() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("merge", true)?;
+    let merge_folder = drawio_exporter.current_dir.join("merge");
+
+    drawio_exporter
+        .cmd
+        .arg("merge")
+        .arg("--output")
+        .arg(drawio_exporter.current_dir.join("merged.drawio.svg"))
+        .arg(merge_folder.join("a.drawio"))
+        .assert()
+        .code(1)
+        .stderr(contains("diagrams can't be embedded in an image"));
+
+    Ok(())
+}
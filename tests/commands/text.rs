@@ -0,0 +1,93 @@
+use crate::DrawioExporterCommand;
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicates::prelude::predicate::str::contains;
+
+#[test]
+fn text_content_as_search_index() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("text", true)?;
+
+    let output = r#"[
+  {
+    "file": "text/text.drawio",
+    "page": 1,
+    "name": "Page-1",
+    "id": "text-page-1",
+    "labels": [
+      "Web Server",
+      "Database"
+    ],
+    "tooltips": [
+      "Stores the orders"
+    ],
+    "attributes": [
+      {
+        "name": "owner",
+        "value": "team-data"
+      },
+      {
+        "name": "version",
+        "value": "14"
+      }
+    ]
+  }
+]"#;
+
+    drawio_exporter
+        .cmd
+        .arg("text")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(output));
+
+    Ok(())
+}
+
+#[test]
+fn text_content_from_html_labels() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("html_links", true)?;
+
+    let output = r#""labels": [
+      "First and Second",
+      "Single Quoted",
+      "Nested formatting tags",
+      "Text <without> link & – — entities"
+    ]"#;
+
+    drawio_exporter
+        .cmd
+        .arg("text")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(output));
+
+    Ok(())
+}
+
+#[test]
+fn text_content_with_placeholders() -> Result<()> {
+    let mut drawio_exporter = DrawioExporterCommand::new_using_data("placeholders", true)?;
+
+    let output = r#""labels": [
+      "Release 1.0 of drawio-exporter",
+      "Overview (1/2) of placeholders.drawio",
+      "Built with %release%",
+      "50% off 2.0",
+      "Raw %page%"
+    ],
+    "tooltips": [
+      "Owned by docs"
+    ]"#;
+
+    drawio_exporter
+        .cmd
+        .arg("text")
+        .arg(&drawio_exporter.current_dir)
+        .assert()
+        .success()
+        .stdout(contains(output));
+
+    Ok(())
+}